@@ -5,6 +5,11 @@ fn main() {
     use_feature_or_nothing("can_vector"); // https://github.com/rust-lang/rust/issues/69941
     use_feature_or_nothing("write_all_vectored"); // https://github.com/rust-lang/rust/issues/70436
 
+    // `BorrowedCursor` is gated separately from `Read::read_buf`.
+    if has_feature("read_buf") && has_feature("core_io_borrowed_buf") {
+        use_feature("read_buf"); // https://github.com/rust-lang/rust/issues/78485
+    }
+
     use_feature("io_lifetimes_use_std");
 
     // Don't rerun this on changes other than build.rs, as we only depend on
@@ -0,0 +1,215 @@
+//! Receiving grips passed by systemd socket activation.
+//!
+//! With socket activation, systemd passes a service its listening sockets,
+//! and other file descriptors, starting at fd 3, and describes them with the
+//! `LISTEN_PID`, `LISTEN_FDS`, and `LISTEN_FDNAMES` environment variables.
+//! [`take_activated_grips`] takes ownership of them.
+
+use crate::grip::{kind, GripKind, OwnedGrip};
+use crate::os::rustix::{FromRawFd, RawFd};
+use ::rustix::io::{fcntl_setfd, FdFlags};
+use io_lifetimes::{AsFd, BorrowedFd};
+use std::env;
+use std::ffi::OsString;
+use std::io;
+use std::net::{TcpListener, UdpSocket};
+use std::os::unix::net::UnixListener;
+use std::sync::{Mutex, PoisonError};
+
+/// The first file descriptor passed by socket activation.
+const LISTEN_FDS_START: RawFd = 3;
+
+/// Whether the grips have been taken.
+static TAKEN: Mutex<bool> = Mutex::new(false);
+
+/// A grip passed by socket activation, with its name.
+#[derive(Debug)]
+pub struct ActivatedGrip {
+    grip: OwnedGrip,
+    name: String,
+}
+
+impl ActivatedGrip {
+    /// Returns the name systemd gave this grip, from `FileDescriptorName=`
+    /// in the socket unit, or `"unknown"` if it wasn't given names.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Determines what kind of object this grip refers to.
+    #[inline]
+    pub fn kind(&self) -> io::Result<GripKind> {
+        kind(&self.grip)
+    }
+
+    /// Returns the underlying grip.
+    #[inline]
+    pub fn into_grip(self) -> OwnedGrip {
+        self.grip
+    }
+
+    /// Converts this grip into a `TcpListener`, failing if it isn't a
+    /// listening TCP socket.
+    ///
+    /// On failure, the grip is closed. Use [`kind`] to check first.
+    ///
+    /// [`kind`]: Self::kind
+    #[inline]
+    pub fn into_tcp_listener(self) -> io::Result<TcpListener> {
+        self.expect(GripKind::TcpListener).map(TcpListener::from)
+    }
+
+    /// Converts this grip into a `UnixListener`, failing if it isn't a
+    /// listening Unix-domain stream socket.
+    ///
+    /// On failure, the grip is closed. Use [`kind`] to check first.
+    ///
+    /// [`kind`]: Self::kind
+    #[inline]
+    pub fn into_unix_listener(self) -> io::Result<UnixListener> {
+        self.expect(GripKind::UnixListener).map(UnixListener::from)
+    }
+
+    /// Converts this grip into a `UdpSocket`, failing if it isn't a UDP
+    /// socket.
+    ///
+    /// On failure, the grip is closed. Use [`kind`] to check first.
+    ///
+    /// [`kind`]: Self::kind
+    #[inline]
+    pub fn into_udp_socket(self) -> io::Result<UdpSocket> {
+        self.expect(GripKind::UdpSocket).map(UdpSocket::from)
+    }
+
+    fn expect(self, expected: GripKind) -> io::Result<OwnedGrip> {
+        let actual = self.kind()?;
+        if actual != expected {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "activated grip {:?} is a {:?}, not a {:?}",
+                    self.name, actual, expected
+                ),
+            ));
+        }
+        Ok(self.grip)
+    }
+}
+
+impl AsFd for ActivatedGrip {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.grip.as_fd()
+    }
+}
+
+/// Takes ownership of the grips passed by systemd socket activation.
+///
+/// This returns an empty list if the process wasn't socket-activated, or if
+/// `LISTEN_PID` names a different process, such as a parent which didn't
+/// clear it. Only the first successful call in a process takes the grips;
+/// later calls return an empty list. This unsets `LISTEN_PID`, `LISTEN_FDS`,
+/// and `LISTEN_FDNAMES`, so that child processes don't see them, and sets
+/// close-on-exec on the grips.
+///
+/// This fails if the variables are malformed, or if any of the file
+/// descriptors isn't open, has close-on-exec set, or has already been taken
+/// by [`take_grip_from_arg`] or [`take_grip_from_env`], as described there.
+/// On failure, nothing is taken, and the variables are left set, except
+/// that in the unlikely event that setting close-on-exec on one of the file
+/// descriptors fails, it and those before it are closed.
+///
+/// Like [`std::env::remove_var`], this shouldn't be called while other
+/// threads may be accessing the environment.
+///
+/// [`take_grip_from_arg`]: crate::inherit::take_grip_from_arg
+/// [`take_grip_from_env`]: crate::inherit::take_grip_from_env
+pub fn take_activated_grips() -> io::Result<Vec<ActivatedGrip>> {
+    let mut taken = TAKEN.lock().unwrap_or_else(PoisonError::into_inner);
+    if *taken {
+        return Ok(Vec::new());
+    }
+
+    let (pid, fds) = match (env::var_os("LISTEN_PID"), env::var_os("LISTEN_FDS")) {
+        (Some(pid), Some(fds)) => (parse(pid, "LISTEN_PID")?, parse(fds, "LISTEN_FDS")?),
+        _ => return Ok(Vec::new()),
+    };
+    if pid != u64::from(std::process::id()) {
+        // These are meant for another process, so don't pass them on.
+        *taken = true;
+        remove_vars();
+        return Ok(Vec::new());
+    }
+    let count = usize::try_from(fds)
+        .ok()
+        .filter(|count| *count <= (RawFd::MAX - LISTEN_FDS_START) as usize)
+        .ok_or_else(|| invalid("LISTEN_FDS is out of range"))?;
+
+    let names: Vec<String> = match env::var_os("LISTEN_FDNAMES") {
+        Some(names) => {
+            let names = names
+                .into_string()
+                .map_err(|_| invalid("LISTEN_FDNAMES isn't valid UTF-8"))?;
+            // An empty string has no names, rather than one empty name.
+            if names.is_empty() {
+                Vec::new()
+            } else {
+                names.split(':').map(str::to_owned).collect()
+            }
+        }
+        None => vec!["unknown".to_owned(); count],
+    };
+    if names.len() != count {
+        return Err(invalid("LISTEN_FDNAMES doesn't match LISTEN_FDS"));
+    }
+
+    // Check all the file descriptors before taking ownership of any.
+    let raw_fds = (LISTEN_FDS_START..).take(count);
+    crate::inherit::claim(raw_fds.clone())?;
+
+    let grips = raw_fds
+        .clone()
+        .zip(names)
+        .map(|(raw_fd, name)| {
+            // Safety: `LISTEN_FDS` passes these file descriptors to this
+            // process, and `claim` has checked that they're open and not
+            // owned elsewhere in this process, and ensures that we only take
+            // ownership of them once.
+            let grip = unsafe { OwnedGrip::from_raw_fd(raw_fd) };
+            fcntl_setfd(&grip, FdFlags::CLOEXEC)?;
+            Ok(ActivatedGrip { grip, name })
+        })
+        .collect::<io::Result<Vec<_>>>();
+    match grips {
+        Ok(grips) => {
+            *taken = true;
+            remove_vars();
+            Ok(grips)
+        }
+        Err(err) => {
+            // The grips built so far have been dropped, closing them, so
+            // forget that any of these were taken.
+            crate::inherit::unclaim(raw_fds);
+            Err(err)
+        }
+    }
+}
+
+fn remove_vars() {
+    env::remove_var("LISTEN_PID");
+    env::remove_var("LISTEN_FDS");
+    env::remove_var("LISTEN_FDNAMES");
+}
+
+fn parse(value: OsString, var: &str) -> io::Result<u64> {
+    value
+        .into_string()
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| invalid(format!("{} isn't a valid number", var)))
+}
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
@@ -0,0 +1,85 @@
+//! `GripAddr`, the address of a socket of any family.
+
+use ::rustix::net::{AddressFamily, SocketAddrAny, SocketAddrUnix};
+use std::io;
+use std::net::SocketAddr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+
+/// The address of an IPv4, IPv6, or Unix-domain socket.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum GripAddr {
+    /// An IPv4 or IPv6 address.
+    Inet(SocketAddr),
+
+    /// A Unix-domain socket bound to a path.
+    Unix(PathBuf),
+
+    /// A Unix-domain socket bound to a name in the abstract namespace.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    UnixAbstract(Vec<u8>),
+
+    /// A Unix-domain socket which isn't bound to a name, such as one end of
+    /// a socket pair, or a client which didn't call `bind`.
+    UnixUnnamed,
+
+    /// An address of some other family.
+    Other,
+}
+
+impl GripAddr {
+    pub(crate) fn from_rustix(addr: Option<SocketAddrAny>) -> Self {
+        let addr = match addr {
+            Some(addr) => addr,
+            None => return Self::UnixUnnamed,
+        };
+        match addr.address_family() {
+            AddressFamily::INET | AddressFamily::INET6 => {
+                SocketAddr::try_from(addr).map_or(Self::Other, Self::Inet)
+            }
+            AddressFamily::UNIX => match SocketAddrUnix::try_from(addr) {
+                Ok(unix) => Self::from_unix(&unix),
+                Err(_) => Self::Other,
+            },
+            _ => Self::Other,
+        }
+    }
+
+    fn from_unix(unix: &SocketAddrUnix) -> Self {
+        if unix.is_unnamed() {
+            return Self::UnixUnnamed;
+        }
+        if let Some(path) = unix.path_bytes() {
+            return Self::Unix(std::ffi::OsStr::from_bytes(path).into());
+        }
+        // Some platforms report unnamed addresses as an empty abstract name.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        if let Some(name) = unix.abstract_name().filter(|name| !name.is_empty()) {
+            return Self::UnixAbstract(name.to_vec());
+        }
+        Self::UnixUnnamed
+    }
+
+    pub(crate) fn to_rustix(&self) -> io::Result<SocketAddrAny> {
+        Ok(match self {
+            Self::Inet(addr) => SocketAddrAny::from(*addr),
+            Self::Unix(path) => SocketAddrUnix::new(path.as_path())?.into(),
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            Self::UnixAbstract(name) => SocketAddrUnix::new_abstract_name(name)?.into(),
+            Self::UnixUnnamed | Self::Other => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "can't send to an unnamed or unknown address",
+                ))
+            }
+        })
+    }
+}
+
+impl From<SocketAddr> for GripAddr {
+    #[inline]
+    fn from(addr: SocketAddr) -> Self {
+        Self::Inet(addr)
+    }
+}
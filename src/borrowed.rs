@@ -1,14 +1,24 @@
 //! `BorrowedReadable` and `BorrowedWriteable`.
 
-use crate::grip::{AsRawGrip, BorrowedGrip, FromRawGrip};
+use crate::grip::{AsGrip, AsRawGrip, BorrowedGrip, FromGrip, FromRawGrip};
 #[cfg(windows)]
 use crate::os::windows::{AsHandleOrSocket, AsRawHandleOrSocket, BorrowedHandleOrSocket};
+use crate::owned::{OwnedReadable, OwnedWriteable};
 use crate::raw::{RawReadable, RawWriteable};
+use crate::sigpipe::NoSigpipe;
+#[cfg(unix)]
+use ::rustix::net::RecvFlags;
+#[cfg(feature = "bytes")]
+use bytes::BytesMut;
 #[cfg(not(windows))]
 use io_lifetimes::{AsFd, BorrowedFd};
 use std::fmt;
+#[cfg(read_buf)]
+use std::io::BorrowedCursor;
 use std::io::{self, IoSlice, IoSliceMut, Read, Write};
 use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::net::Shutdown;
 #[cfg(all(doc, not(windows)))]
 use std::net::TcpStream;
 #[cfg(unix)]
@@ -56,6 +66,97 @@ impl<'a> BorrowedReadable<'a> {
             _phantom: PhantomData,
         }
     }
+
+    /// Creates a new `OwnedReadable` for the same underlying handle.
+    ///
+    /// See [`grip::try_clone_to_owned`] for details.
+    ///
+    /// [`grip::try_clone_to_owned`]: crate::grip::try_clone_to_owned
+    #[inline]
+    pub fn try_clone_to_owned(&self) -> io::Result<OwnedReadable> {
+        Ok(OwnedReadable::from_grip(crate::grip::try_clone_to_owned(
+            self,
+        )?))
+    }
+
+    /// Receives data from a socket without removing it from the input queue.
+    ///
+    /// See [`RawReadable::peek`] for details.
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.raw.peek(buf)
+    }
+
+    /// Receives data from a socket, passing `flags` to the underlying `recv`
+    /// call.
+    ///
+    /// See [`RawReadable::recv_with_flags`] for details.
+    #[cfg(unix)]
+    #[inline]
+    pub fn recv_with_flags(&self, buf: &mut [u8], flags: RecvFlags) -> io::Result<(usize, usize)> {
+        self.raw.recv_with_flags(buf, flags)
+    }
+
+    /// Like [`Read::read`], but reads into a possibly-uninitialized buffer.
+    ///
+    /// See [`RawReadable::read_uninit`] for details.
+    #[inline]
+    pub fn read_uninit(&mut self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+        self.raw.read_uninit(buf)
+    }
+
+    /// Reads into the spare capacity of `buf`, and extends its length by the
+    /// number of bytes read.
+    ///
+    /// See [`RawReadable::read_buf`] for details.
+    #[cfg(feature = "bytes")]
+    #[inline]
+    pub fn read_buf(&mut self, buf: &mut BytesMut) -> io::Result<usize> {
+        self.raw.read_buf(buf)
+    }
+
+    /// Returns the number of bytes that can currently be read.
+    ///
+    /// See [`grip::bytes_available`] for details.
+    ///
+    /// [`grip::bytes_available`]: crate::grip::bytes_available
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn bytes_available(&self) -> io::Result<u64> {
+        crate::grip::bytes_available(self)
+    }
+
+    /// Tests whether the other end has hung up, without consuming any data.
+    ///
+    /// See [`grip::is_peer_closed`] for details.
+    ///
+    /// [`grip::is_peer_closed`]: crate::grip::is_peer_closed
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn is_peer_closed(&self) -> io::Result<bool> {
+        crate::grip::is_peer_closed(self)
+    }
+
+    /// Shuts down the read half of a socket.
+    ///
+    /// See [`grip::shutdown`] for details.
+    ///
+    /// [`grip::shutdown`]: crate::grip::shutdown
+    #[inline]
+    pub fn shutdown_read(&self) -> io::Result<()> {
+        crate::grip::shutdown(self, Shutdown::Read)
+    }
+
+    /// Tests whether reads have an efficient vectored implementation.
+    ///
+    /// See [`vectored::is_read_vectored`] for details.
+    ///
+    /// [`vectored::is_read_vectored`]: crate::vectored::is_read_vectored
+    #[inline]
+    pub fn is_read_vectored(&self) -> bool {
+        crate::vectored::is_read_vectored(self)
+    }
 }
 
 impl<'a> BorrowedWriteable<'a> {
@@ -68,6 +169,74 @@ impl<'a> BorrowedWriteable<'a> {
             _phantom: PhantomData,
         }
     }
+
+    /// Creates a new `OwnedWriteable` for the same underlying handle.
+    ///
+    /// See [`grip::try_clone_to_owned`] for details.
+    ///
+    /// [`grip::try_clone_to_owned`]: crate::grip::try_clone_to_owned
+    #[inline]
+    pub fn try_clone_to_owned(&self) -> io::Result<OwnedWriteable> {
+        Ok(OwnedWriteable::from_grip(crate::grip::try_clone_to_owned(
+            self,
+        )?))
+    }
+
+    /// Tests whether the other end has hung up, without consuming any data.
+    ///
+    /// See [`grip::is_peer_closed`] for details.
+    ///
+    /// [`grip::is_peer_closed`]: crate::grip::is_peer_closed
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn is_peer_closed(&self) -> io::Result<bool> {
+        crate::grip::is_peer_closed(self)
+    }
+
+    /// Shuts down the write half of a socket.
+    ///
+    /// See [`grip::shutdown`] for details.
+    ///
+    /// [`grip::shutdown`]: crate::grip::shutdown
+    #[inline]
+    pub fn shutdown_write(&self) -> io::Result<()> {
+        crate::grip::shutdown(self, Shutdown::Write)
+    }
+
+    /// Tests whether writes have an efficient vectored implementation.
+    ///
+    /// See [`vectored::is_write_vectored`] for details.
+    ///
+    /// [`vectored::is_write_vectored`]: crate::vectored::is_write_vectored
+    #[inline]
+    pub fn is_write_vectored(&self) -> bool {
+        crate::vectored::is_write_vectored(self)
+    }
+
+    /// Returns a writer which writes to this handle without raising
+    /// `SIGPIPE`.
+    ///
+    /// See [`NoSigpipe`] for details.
+    #[inline]
+    pub fn no_sigpipe(&self) -> NoSigpipe<BorrowedWriteable<'_>> {
+        NoSigpipe::new(BorrowedWriteable::borrow(self.as_grip()))
+    }
+
+    /// Synchronizes the file's content and metadata to storage.
+    ///
+    /// See [`RawWriteable::sync_all`] for details.
+    #[inline]
+    pub fn sync_all(&self) -> io::Result<()> {
+        self.raw.sync_all()
+    }
+
+    /// Synchronizes the file's content to storage.
+    ///
+    /// See [`RawWriteable::sync_data`] for details.
+    #[inline]
+    pub fn sync_data(&self) -> io::Result<()> {
+        self.raw.sync_data()
+    }
 }
 
 /// `BorrowedReadable` borrows its handle.
@@ -140,6 +309,12 @@ impl<'a> Read for BorrowedReadable<'a> {
     fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
         self.raw.read_exact(buf)
     }
+
+    #[cfg(read_buf)]
+    #[inline]
+    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> io::Result<()> {
+        Read::read_buf(&mut self.raw, cursor)
+    }
 }
 
 #[cfg(windows)]
@@ -174,6 +349,12 @@ impl<'a> Read for BorrowedReadable<'a> {
     fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
         self.raw.read_exact(buf)
     }
+
+    #[cfg(read_buf)]
+    #[inline]
+    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> io::Result<()> {
+        Read::read_buf(&mut self.raw, cursor)
+    }
 }
 
 #[cfg(not(windows))]
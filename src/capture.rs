@@ -0,0 +1,113 @@
+//! Capturing the process' stdio at the file descriptor level, for tests.
+//!
+//! Test harnesses such as libtest capture output written with `print!`, but
+//! not output written directly to file descriptors 1 and 2, such as through
+//! [`RawWriteable`] or by C libraries. [`capture_stdio`] replaces fds 0, 1,
+//! and 2 with pipes while running a closure, feeding it prepared input and
+//! collecting its output.
+//!
+//! [`RawWriteable`]: crate::raw::RawWriteable
+
+use crate::redirect::{redirect_stderr, redirect_stdin, redirect_stdout};
+use crate::sigpipe::{is_peer_gone, NoSigpipe};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::sync::{Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+
+/// Serializes captures, since stdio is shared by the whole process.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// The result of [`capture_stdio`].
+#[allow(clippy::exhaustive_structs)]
+#[derive(Debug)]
+pub struct Captured<R> {
+    /// The value returned by the closure.
+    pub value: R,
+
+    /// The bytes written to fd 1.
+    pub stdout: Vec<u8>,
+
+    /// The bytes written to fd 2.
+    pub stderr: Vec<u8>,
+}
+
+/// Calls `f` with fd 0 reading from `stdin`, and fds 1 and 2 captured.
+///
+/// Once `stdin` is exhausted, reads from fd 0 see end-of-stream. Captures
+/// are serialized with a process-wide lock, so concurrent tests which use
+/// this don't interfere with each other. Other threads which write to fds 1
+/// and 2 while `f` runs, including a test harness reporting results of
+/// other tests, have their output captured too.
+///
+/// Output written with `print!` and `eprint!` goes through libtest's own
+/// capture when running under libtest, rather than to fds 1 and 2.
+pub fn capture_stdio<R>(stdin: &[u8], f: impl FnOnce() -> R) -> io::Result<Captured<R>> {
+    let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let (stdin_reader, stdin_writer) = pipe()?;
+    let (stdout_reader, stdout_writer) = pipe()?;
+    let (stderr_reader, stderr_writer) = pipe()?;
+
+    // Feed and drain the pipes from other threads, so that `f` doesn't
+    // block on a full pipe.
+    let feeder = feed(stdin_writer, stdin.to_vec());
+    let stdout = drain(stdout_reader);
+    let stderr = drain(stderr_reader);
+
+    let value = {
+        let _stdin = redirect_stdin(&stdin_reader)?;
+        let _stdout = redirect_stdout(&stdout_writer)?;
+        let _stderr = redirect_stderr(&stderr_writer)?;
+        drop((stdin_reader, stdout_writer, stderr_writer));
+        f()
+    };
+
+    // Restoring the stdio fds closed the last handles to the pipes, so the
+    // threads can finish now.
+    let feeder = feeder.join().unwrap();
+    let stdout = stdout.join().unwrap()?;
+    let stderr = stderr.join().unwrap()?;
+    match feeder {
+        Err(err) if !is_peer_gone(&err) => return Err(err),
+        _ => (),
+    }
+
+    Ok(Captured {
+        value,
+        stdout,
+        stderr,
+    })
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+fn pipe() -> io::Result<(File, File)> {
+    use ::rustix::pipe::{pipe_with, PipeFlags};
+
+    let (reader, writer) = pipe_with(PipeFlags::CLOEXEC)?;
+    Ok((reader.into(), writer.into()))
+}
+
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+fn pipe() -> io::Result<(File, File)> {
+    use ::rustix::io::{fcntl_setfd, FdFlags};
+
+    let (reader, writer) = ::rustix::pipe::pipe()?;
+    fcntl_setfd(&reader, FdFlags::CLOEXEC)?;
+    fcntl_setfd(&writer, FdFlags::CLOEXEC)?;
+    Ok((reader.into(), writer.into()))
+}
+
+fn feed(writer: File, input: Vec<u8>) -> JoinHandle<io::Result<()>> {
+    // If `f` doesn't read all the input, the reading end is closed while
+    // we're writing, so avoid `SIGPIPE`.
+    thread::spawn(move || NoSigpipe::new(writer).write_all(&input))
+}
+
+fn drain(mut reader: File) -> JoinHandle<io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        reader.read_to_end(&mut output)?;
+        Ok(output)
+    })
+}
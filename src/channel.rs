@@ -0,0 +1,428 @@
+//! Typed channels which carry grips, over `SOCK_SEQPACKET` sockets.
+//!
+//! [`channel`] creates a connected [`Sender`] and [`Receiver`]. Each message
+//! is a value, serialized with its [`Message`] implementation, together with
+//! any number of grips, up to [`MAX_GRIPS`]. Either end can be passed to
+//! another process, such as with [`CommandGripExt`], and reconstructed there
+//! with `From<OwnedFd>`, so that one process can hand opened files and
+//! sockets to another.
+//!
+//! [`CommandGripExt`]: crate::command::CommandGripExt
+
+use crate::grip::{BorrowedGrip, OwnedGrip};
+use crate::passing::{recv_grips, send_grips};
+use ::rustix::net::{socketpair, AddressFamily, SocketFlags, SocketType};
+use io_lifetimes::{AsFd, BorrowedFd, OwnedFd};
+use std::fmt;
+use std::io;
+use std::marker::PhantomData;
+#[cfg(all(feature = "serde", feature = "bincode"))]
+use {
+    ::bincode::Options,
+    ::serde::{de::DeserializeOwned, Serialize},
+};
+
+/// The maximum number of grips in one message, which is Linux's limit on
+/// the number of file descriptors in one `SCM_RIGHTS` message.
+pub const MAX_GRIPS: usize = 253;
+
+/// The default limit on the size of a message's serialized data, which
+/// [`Sender::set_max_message_size`] and [`Receiver::set_max_message_size`]
+/// change.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// The size of the packets message data is split into, which is comfortably
+/// below the default socket buffer sizes.
+const CHUNK_SIZE: usize = 32 * 1024;
+
+/// The size of the header packet, which holds the data length and the number
+/// of grips, as little-endian `u32`s, and carries the grips.
+const HEADER_SIZE: usize = 8;
+
+/// A value which can be sent over a [`Sender`].
+///
+/// This is implemented for byte vectors, strings, and `()`, and, with the
+/// `use_serde` feature, for any serde type wrapped in `Serde`. To send
+/// other types, implement this with the serialization format of your choice.
+pub trait Message: Sized {
+    /// Appends the serialized form of `self` to `buf`.
+    fn encode(&self, buf: &mut Vec<u8>) -> io::Result<()>;
+
+    /// Deserializes a value from `bytes`.
+    fn decode(bytes: &[u8]) -> io::Result<Self>;
+}
+
+impl Message for Vec<u8> {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) -> io::Result<()> {
+        buf.extend_from_slice(self);
+        Ok(())
+    }
+
+    #[inline]
+    fn decode(bytes: &[u8]) -> io::Result<Self> {
+        Ok(bytes.to_vec())
+    }
+}
+
+impl Message for String {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) -> io::Result<()> {
+        buf.extend_from_slice(self.as_bytes());
+        Ok(())
+    }
+
+    #[inline]
+    fn decode(bytes: &[u8]) -> io::Result<Self> {
+        String::from_utf8(bytes.to_vec())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl Message for () {
+    #[inline]
+    fn encode(&self, _buf: &mut Vec<u8>) -> io::Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    fn decode(bytes: &[u8]) -> io::Result<Self> {
+        if bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "expected an empty message",
+            ))
+        }
+    }
+}
+
+/// A wrapper which sends any serde type as a [`Message`], encoded with
+/// bincode.
+///
+/// Decoding rejects data with trailing bytes, and never allocates more than
+/// the size of the data it's decoding.
+#[cfg(all(feature = "serde", feature = "bincode"))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Serde<T>(pub T);
+
+#[cfg(all(feature = "serde", feature = "bincode"))]
+impl<T: Serialize + DeserializeOwned> Message for Serde<T> {
+    fn encode(&self, buf: &mut Vec<u8>) -> io::Result<()> {
+        ::bincode::DefaultOptions::new()
+            .serialize_into(buf, &self.0)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+    }
+
+    fn decode(bytes: &[u8]) -> io::Result<Self> {
+        ::bincode::DefaultOptions::new()
+            .with_limit(bytes.len() as u64)
+            .reject_trailing_bytes()
+            .deserialize(bytes)
+            .map(Self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+/// Creates a connected [`Sender`] and [`Receiver`], with close-on-exec set.
+pub fn channel<T: Message>() -> io::Result<(Sender<T>, Receiver<T>)> {
+    let (sender, receiver) = socketpair(
+        AddressFamily::UNIX,
+        SocketType::SEQPACKET,
+        SocketFlags::CLOEXEC,
+        None,
+    )?;
+    Ok((Sender::from(sender), Receiver::from(receiver)))
+}
+
+/// The sending end of a [`channel`].
+pub struct Sender<T> {
+    socket: OwnedFd,
+    buf: Vec<u8>,
+    max_message_size: usize,
+    poisoned: bool,
+    _phantom: PhantomData<fn(&T)>,
+}
+
+impl<T> Sender<T> {
+    /// Returns the limit on the size of a message's serialized data.
+    #[inline]
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    /// Sets the limit on the size of a message's serialized data, which is
+    /// [`DEFAULT_MAX_MESSAGE_SIZE`] by default. The receiver must allow
+    /// messages at least this large.
+    #[inline]
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+}
+
+impl<T: Message> Sender<T> {
+    /// Sends `value`, with duplicates of `grips` attached.
+    ///
+    /// This blocks if the receiver isn't keeping up. If the receiver has been
+    /// closed, this fails with [`io::ErrorKind::BrokenPipe`]. If `value`'s
+    /// serialized data is larger than [`max_message_size`], this fails with
+    /// [`io::ErrorKind::InvalidInput`], and nothing is sent.
+    ///
+    /// If sending fails partway through a message, the receiver can't tell
+    /// where the next message starts, so this sender is poisoned, and later
+    /// calls fail.
+    ///
+    /// [`max_message_size`]: Self::max_message_size
+    pub fn send(&mut self, value: &T, grips: &[BorrowedGrip<'_>]) -> io::Result<()> {
+        if self.poisoned {
+            return Err(poisoned());
+        }
+        if grips.len() > MAX_GRIPS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("can't send more than {} grips in a message", MAX_GRIPS),
+            ));
+        }
+
+        self.buf.clear();
+        value.encode(&mut self.buf)?;
+        let len = u32::try_from(self.buf.len())
+            .ok()
+            .filter(|_| self.buf.len() <= self.max_message_size)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "message of {} bytes is larger than the limit of {} bytes",
+                        self.buf.len(),
+                        self.max_message_size
+                    ),
+                )
+            })?;
+
+        let mut header = [0_u8; HEADER_SIZE];
+        header[..4].copy_from_slice(&len.to_le_bytes());
+        header[4..].copy_from_slice(&(grips.len() as u32).to_le_bytes());
+        // Packets on a `SOCK_SEQPACKET` socket are sent whole or not at all,
+        // so if the header isn't sent, nothing is.
+        send_packet(&self.socket, &header, grips)?;
+
+        for chunk in self.buf.chunks(CHUNK_SIZE) {
+            if let Err(err) = send_packet(&self.socket, chunk, &[]) {
+                self.poisoned = true;
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The receiving end of a [`channel`].
+pub struct Receiver<T> {
+    socket: OwnedFd,
+    buf: Vec<u8>,
+    max_message_size: usize,
+    poisoned: bool,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Receiver<T> {
+    /// Returns the limit on the size of a message's serialized data.
+    #[inline]
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    /// Sets the limit on the size of a message's serialized data, which is
+    /// [`DEFAULT_MAX_MESSAGE_SIZE`] by default.
+    ///
+    /// This bounds how much memory a sender can make this receiver
+    /// allocate, which matters when the sender isn't trusted.
+    #[inline]
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+}
+
+impl<T: Message> Receiver<T> {
+    /// Receives a value and its attached grips, blocking until one arrives.
+    ///
+    /// Returns `None` if the sender has been closed. The grips have
+    /// close-on-exec set. If the message's data is larger than
+    /// [`max_message_size`], this fails with [`io::ErrorKind::InvalidData`].
+    ///
+    /// If an error leaves part of a message unread, this can't tell where the
+    /// next message starts, so this receiver is poisoned, and later calls
+    /// fail. An error decoding a complete message doesn't poison it.
+    ///
+    /// [`max_message_size`]: Self::max_message_size
+    pub fn recv(&mut self) -> io::Result<Option<(T, Vec<OwnedGrip>)>> {
+        if self.poisoned {
+            return Err(poisoned());
+        }
+        match self.recv_data()? {
+            Some(grips) => Ok(Some((T::decode(&self.buf)?, grips))),
+            None => Ok(None),
+        }
+    }
+
+    /// Receives a message's data into `self.buf`, and returns its grips,
+    /// poisoning this receiver if an error leaves part of a message unread.
+    fn recv_data(&mut self) -> io::Result<Option<Vec<OwnedGrip>>> {
+        let mut header = [0_u8; HEADER_SIZE];
+        let (n, grips) = match recv_packet(&self.socket, &mut header, MAX_GRIPS) {
+            Ok(received) => received,
+            Err(err) => {
+                // `recv_grips` consumes malformed packets before reporting
+                // them, and the rest of the message may follow.
+                self.poisoned = err.kind() == io::ErrorKind::InvalidData;
+                return Err(err);
+            }
+        };
+        if n == 0 {
+            return Ok(None);
+        }
+
+        let result = self.recv_body(&header[..n], grips.len());
+        self.poisoned = result.is_err();
+        result.map(|()| Some(grips))
+    }
+
+    /// Parses a message's header, and receives the data it describes.
+    fn recv_body(&mut self, header: &[u8], num_grips: usize) -> io::Result<()> {
+        if header.len() != HEADER_SIZE {
+            return Err(malformed());
+        }
+        let len = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+        let count = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+        if num_grips != count || len > self.max_message_size {
+            return Err(malformed());
+        }
+
+        self.buf.clear();
+        self.buf.resize(len, 0);
+        for chunk in self.buf.chunks_mut(CHUNK_SIZE) {
+            let (n, _) = recv_packet(&self.socket, chunk, 0)?;
+            if n == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "sender closed in the middle of a message",
+                ));
+            }
+            if n != chunk.len() {
+                return Err(malformed());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sends one packet, retrying if interrupted.
+fn send_packet(socket: &OwnedFd, data: &[u8], grips: &[BorrowedGrip<'_>]) -> io::Result<()> {
+    loop {
+        match send_grips(socket, data, grips) {
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            result => return result.map(drop),
+        }
+    }
+}
+
+/// Receives one packet, retrying if interrupted.
+fn recv_packet(
+    socket: &OwnedFd,
+    buf: &mut [u8],
+    max_grips: usize,
+) -> io::Result<(usize, Vec<OwnedGrip>)> {
+    loop {
+        match recv_grips(socket, buf, max_grips) {
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            result => return result,
+        }
+    }
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed channel message")
+}
+
+fn poisoned() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Other,
+        "channel is out of sync after an error in the middle of a message",
+    )
+}
+
+/// Converts an end of a `SOCK_SEQPACKET` socket pair, such as one passed
+/// from another process, into a `Sender`.
+impl<T> From<OwnedFd> for Sender<T> {
+    #[inline]
+    fn from(socket: OwnedFd) -> Self {
+        Self {
+            socket,
+            buf: Vec::new(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            poisoned: false,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Converts an end of a `SOCK_SEQPACKET` socket pair, such as one passed
+/// from another process, into a `Receiver`.
+impl<T> From<OwnedFd> for Receiver<T> {
+    #[inline]
+    fn from(socket: OwnedFd) -> Self {
+        Self {
+            socket,
+            buf: Vec::new(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            poisoned: false,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> From<Sender<T>> for OwnedFd {
+    #[inline]
+    fn from(sender: Sender<T>) -> Self {
+        sender.socket
+    }
+}
+
+impl<T> From<Receiver<T>> for OwnedFd {
+    #[inline]
+    fn from(receiver: Receiver<T>) -> Self {
+        receiver.socket
+    }
+}
+
+impl<T> AsFd for Sender<T> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.socket.as_fd()
+    }
+}
+
+impl<T> AsFd for Receiver<T> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.socket.as_fd()
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("socket", &self.socket)
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("socket", &self.socket)
+            .finish()
+    }
+}
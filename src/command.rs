@@ -0,0 +1,260 @@
+//! Passing grips to child processes at chosen file descriptor numbers.
+
+use crate::grip::AsGrip;
+use crate::os::rustix::{AsRawFd, RawFd};
+use io_lifetimes::OwnedFd;
+use std::ffi::OsStr;
+use std::io;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+
+/// The lowest number [`CommandGripExt::grip_arg`] and
+/// [`CommandGripExt::grip_env`] use, to stay clear of the small numbers
+/// typically passed to [`CommandGripExt::grip`].
+const INHERIT_MIN: RawFd = 10;
+
+/// Extension trait for process builders, for passing grips to the child.
+pub trait CommandGripExt {
+    /// Arranges for the child process to have a duplicate of `grip` at file
+    /// descriptor number `child_fd`, without close-on-exec.
+    ///
+    /// `grip` is duplicated immediately, with close-on-exec set, so it may be
+    /// closed in the parent right away, and the duplicate is only inherited
+    /// by this child. Mappings are applied in the order they're added, and
+    /// after stdin, stdout, and stderr are set up, so a mapping to fd 0, 1,
+    /// or 2 overrides them. Overlaps and cycles between source and target
+    /// numbers, such as passing the parent's fds 3 and 4 as the child's 4
+    /// and 3, are handled.
+    ///
+    /// If duplicating `grip` fails, spawning the child fails with the error.
+    fn grip<Grip: AsGrip>(&mut self, child_fd: RawFd, grip: Grip) -> &mut Self;
+
+    /// Passes a duplicate of `grip` to the child process, and appends an
+    /// argument of `prefix` followed by its number, such as `--fd=12`.
+    ///
+    /// The duplicate is held open until the child is spawned, and is only
+    /// inherited by this child. The child can take ownership of it with
+    /// [`take_grip_from_arg`]. If a mapping added with [`grip`] targets the
+    /// same number, spawning fails.
+    ///
+    /// [`take_grip_from_arg`]: crate::inherit::take_grip_from_arg
+    /// [`grip`]: Self::grip
+    fn grip_arg<Grip: AsGrip>(&mut self, prefix: &str, grip: Grip) -> &mut Self;
+
+    /// Passes a duplicate of `grip` to the child process, and sets the
+    /// environment variable `name` to its number.
+    ///
+    /// The duplicate is held open until the child is spawned, and is only
+    /// inherited by this child. The child can take ownership of it with
+    /// [`take_grip_from_env`]. If a mapping added with [`grip`] targets the
+    /// same number, spawning fails.
+    ///
+    /// [`take_grip_from_env`]: crate::inherit::take_grip_from_env
+    /// [`grip`]: Self::grip
+    fn grip_env<Grip: AsGrip, K: AsRef<OsStr>>(&mut self, name: K, grip: Grip) -> &mut Self;
+}
+
+impl CommandGripExt for Command {
+    #[inline]
+    fn grip<Grip: AsGrip>(&mut self, child_fd: RawFd, grip: Grip) -> &mut Self {
+        let mut mapping = Mapping::new(child_fd, grip);
+        // Safety: `Mapping::install` is async-signal-safe.
+        unsafe { self.pre_exec(move || mapping.install()) }
+    }
+
+    #[inline]
+    fn grip_arg<Grip: AsGrip>(&mut self, prefix: &str, grip: Grip) -> &mut Self {
+        let mut inherited = Inherited::new(grip);
+        self.arg(format!("{}{}", prefix, inherited.number()));
+        // Safety: `Inherited::install` is async-signal-safe.
+        unsafe { self.pre_exec(move || inherited.install()) }
+    }
+
+    #[inline]
+    fn grip_env<Grip: AsGrip, K: AsRef<OsStr>>(&mut self, name: K, grip: Grip) -> &mut Self {
+        let mut inherited = Inherited::new(grip);
+        self.env(name, inherited.number().to_string());
+        // Safety: `Inherited::install` is async-signal-safe.
+        unsafe { self.pre_exec(move || inherited.install()) }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl CommandGripExt for tokio::process::Command {
+    #[inline]
+    fn grip<Grip: AsGrip>(&mut self, child_fd: RawFd, grip: Grip) -> &mut Self {
+        let mut mapping = Mapping::new(child_fd, grip);
+        // Safety: `Mapping::install` is async-signal-safe.
+        unsafe { self.pre_exec(move || mapping.install()) }
+    }
+
+    #[inline]
+    fn grip_arg<Grip: AsGrip>(&mut self, prefix: &str, grip: Grip) -> &mut Self {
+        let mut inherited = Inherited::new(grip);
+        self.arg(format!("{}{}", prefix, inherited.number()));
+        // Safety: `Inherited::install` is async-signal-safe.
+        unsafe { self.pre_exec(move || inherited.install()) }
+    }
+
+    #[inline]
+    fn grip_env<Grip: AsGrip, K: AsRef<OsStr>>(&mut self, name: K, grip: Grip) -> &mut Self {
+        let mut inherited = Inherited::new(grip);
+        self.env(name, inherited.number().to_string());
+        // Safety: `Inherited::install` is async-signal-safe.
+        unsafe { self.pre_exec(move || inherited.install()) }
+    }
+}
+
+/// A grip to install in a child process at a chosen number, held open in
+/// the parent until the child is spawned.
+struct Mapping {
+    target: RawFd,
+    staged: Result<OwnedFd, i32>,
+}
+
+impl Mapping {
+    fn new<Grip: AsGrip>(target: RawFd, grip: Grip) -> Self {
+        let staged = crate::grip::try_clone_to_owned(&grip)
+            .map_err(|err| err.raw_os_error().unwrap_or(libc::EBADF));
+        Self { target, staged }
+    }
+
+    /// Runs in the child between `fork` and `exec`, so this must only do
+    /// async-signal-safe things, and not allocate.
+    fn install(&mut self) -> io::Result<()> {
+        let staged = staged_fd(&self.staged)?;
+
+        // An earlier mapping may have moved our source out of the way.
+        let source = RELOCATED.follow(staged);
+        let target = self.target;
+
+        if PINNED.contains(target) {
+            return Err(io::Error::from_raw_os_error(libc::EBUSY));
+        }
+        if source == target {
+            return clear_cloexec(target);
+        }
+
+        // The target may be the source of a later mapping, so move whatever
+        // is there out of the way first, and record where it went.
+        if unsafe { libc::fcntl(target, libc::F_GETFD) } != -1 {
+            let moved = unsafe { libc::fcntl(target, libc::F_DUPFD_CLOEXEC, 0) };
+            check(moved)?;
+            RELOCATED.push(target, moved)?;
+        }
+
+        // `dup2` clears close-on-exec on the new file descriptor.
+        check(unsafe { libc::dup2(source, target) })
+    }
+}
+
+/// A grip to pass to a child process at whatever number it has in the
+/// parent, held open in the parent until the child is spawned.
+struct Inherited {
+    staged: Result<OwnedFd, i32>,
+}
+
+impl Inherited {
+    fn new<Grip: AsGrip>(grip: Grip) -> Self {
+        let staged = ::rustix::io::fcntl_dupfd_cloexec(grip.as_grip(), INHERIT_MIN)
+            .map_err(|err| err.raw_os_error());
+        Self { staged }
+    }
+
+    /// Returns the number the child will see, or -1 if duplicating failed,
+    /// in which case spawning will fail.
+    fn number(&self) -> RawFd {
+        self.staged.as_ref().map_or(-1, AsRawFd::as_raw_fd)
+    }
+
+    /// Runs in the child between `fork` and `exec`, so this must only do
+    /// async-signal-safe things, and not allocate.
+    fn install(&mut self) -> io::Result<()> {
+        let staged = staged_fd(&self.staged)?;
+
+        // If an earlier mapping targeted our number, it's no longer ours.
+        if RELOCATED.follow(staged) != staged {
+            return Err(io::Error::from_raw_os_error(libc::EBUSY));
+        }
+        PINNED.push(staged, staged)?;
+        clear_cloexec(staged)
+    }
+}
+
+fn staged_fd(staged: &Result<OwnedFd, i32>) -> io::Result<RawFd> {
+    match staged {
+        Ok(staged) => Ok(staged.as_raw_fd()),
+        Err(errno) => Err(io::Error::from_raw_os_error(*errno)),
+    }
+}
+
+fn clear_cloexec(fd: RawFd) -> io::Result<()> {
+    check(unsafe { libc::fcntl(fd, libc::F_SETFD, 0) })
+}
+
+fn check(result: libc::c_int) -> io::Result<()> {
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+const TABLE_SIZE: usize = 256;
+
+/// A fixed-size table of pairs of file descriptors, which can be used
+/// without allocating between `fork` and `exec`.
+///
+/// These are only written in child processes between `fork` and `exec`, so
+/// each child starts with them empty.
+struct FdTable {
+    from: [AtomicI32; TABLE_SIZE],
+    to: [AtomicI32; TABLE_SIZE],
+    len: AtomicUsize,
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const UNUSED: AtomicI32 = AtomicI32::new(-1);
+
+/// File descriptors which mappings have moved out of their way.
+static RELOCATED: FdTable = FdTable::new();
+
+/// File descriptors passed by number, which mappings mustn't replace.
+static PINNED: FdTable = FdTable::new();
+
+impl FdTable {
+    const fn new() -> Self {
+        Self {
+            from: [UNUSED; TABLE_SIZE],
+            to: [UNUSED; TABLE_SIZE],
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    fn push(&self, from: RawFd, to: RawFd) -> io::Result<()> {
+        let index = self.len.load(Ordering::Relaxed);
+        if index == TABLE_SIZE {
+            return Err(io::Error::from_raw_os_error(libc::EMFILE));
+        }
+        self.from[index].store(from, Ordering::Relaxed);
+        self.to[index].store(to, Ordering::Relaxed);
+        self.len.store(index + 1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Follows the entries for `fd`, in the order they were pushed.
+    fn follow(&self, mut fd: RawFd) -> RawFd {
+        for index in 0..self.len.load(Ordering::Relaxed) {
+            if self.from[index].load(Ordering::Relaxed) == fd {
+                fd = self.to[index].load(Ordering::Relaxed);
+            }
+        }
+        fd
+    }
+
+    fn contains(&self, fd: RawFd) -> bool {
+        (0..self.len.load(Ordering::Relaxed))
+            .any(|index| self.from[index].load(Ordering::Relaxed) == fd)
+    }
+}
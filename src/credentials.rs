@@ -0,0 +1,213 @@
+//! Identifying the process on the other end of a Unix-domain socket.
+//!
+//! [`peer_credentials`] returns the credentials of a connected socket's peer,
+//! as of when the connection was made. On Linux, [`peer_pidfd`] returns a
+//! pidfd for the peer process, which, unlike a pid, can't be reused by an
+//! unrelated process, and [`recv_grips_with_credentials`] receives the
+//! sender's credentials with each message.
+//!
+//! [`recv_grips_with_credentials`]: crate::passing::recv_grips_with_credentials
+
+use crate::grip::AsGrip;
+#[cfg(target_os = "linux")]
+use crate::grip::OwnedGrip;
+use std::io;
+
+/// The process ID, user ID, and group ID of a socket's peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PeerCredentials {
+    pid: Option<u32>,
+    uid: u32,
+    gid: u32,
+}
+
+impl PeerCredentials {
+    /// Returns the peer's process ID, on platforms which report it.
+    #[inline]
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    /// Returns the peer's effective user ID.
+    #[inline]
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    /// Returns the peer's effective group ID.
+    #[inline]
+    pub fn gid(&self) -> u32 {
+        self.gid
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub(crate) fn from_ucred(ucred: ::rustix::net::UCred) -> Self {
+        Self {
+            pid: Some(ucred.pid.as_raw_pid() as u32),
+            uid: ucred.uid.as_raw(),
+            gid: ucred.gid.as_raw(),
+        }
+    }
+}
+
+/// Returns the credentials of the peer of the connected Unix-domain socket
+/// `socket`.
+///
+/// These are the peer's credentials as of when it called `connect`, or
+/// `socketpair`, so a peer which later changes its credentials, or passes the
+/// socket to another process, isn't reflected here. The process ID is
+/// available on Linux, Android, and Apple platforms.
+///
+/// This uses `SO_PEERCRED` on Linux and Android, and `getpeereid` on BSDs.
+pub fn peer_credentials<Socket: AsGrip>(socket: &Socket) -> io::Result<PeerCredentials> {
+    imp::peer_credentials(socket)
+}
+
+/// Returns a pidfd for the peer of the connected Unix-domain socket
+/// `socket`, with close-on-exec set.
+///
+/// This uses `SO_PEERPIDFD`, which was added in Linux 6.5, and fails with
+/// [`io::ErrorKind::Unsupported`] on older kernels.
+#[cfg(target_os = "linux")]
+pub fn peer_pidfd<Socket: AsGrip>(socket: &Socket) -> io::Result<OwnedGrip> {
+    imp::peer_pidfd(socket)
+}
+
+/// Enables or disables `SO_PASSCRED` on `socket`, which makes the kernel
+/// attach the sender's credentials to each message it receives.
+///
+/// This must be enabled for [`recv_grips_with_credentials`] to receive
+/// credentials. Messages sent before it's enabled may arrive without them,
+/// so it's best to enable it before the peer starts sending.
+///
+/// [`recv_grips_with_credentials`]: crate::passing::recv_grips_with_credentials
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[inline]
+pub fn set_pass_credentials<Socket: AsGrip>(socket: &Socket, value: bool) -> io::Result<()> {
+    Ok(::rustix::net::sockopt::set_socket_passcred(
+        socket.as_grip(),
+        value,
+    )?)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod imp {
+    use super::PeerCredentials;
+    use crate::grip::AsGrip;
+    use std::io;
+    #[cfg(target_os = "linux")]
+    use {
+        crate::grip::OwnedGrip,
+        crate::os::rustix::{AsRawFd, FromRawFd},
+        std::mem::size_of,
+    };
+
+    pub(super) fn peer_credentials<Socket: AsGrip>(socket: &Socket) -> io::Result<PeerCredentials> {
+        let ucred = ::rustix::net::sockopt::socket_peercred(socket.as_grip())?;
+        Ok(PeerCredentials::from_ucred(ucred))
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(super) fn peer_pidfd<Socket: AsGrip>(socket: &Socket) -> io::Result<OwnedGrip> {
+        let mut pidfd: libc::c_int = -1;
+        let mut len = size_of::<libc::c_int>() as libc::socklen_t;
+        let result = unsafe {
+            libc::getsockopt(
+                socket.as_grip().as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_PEERPIDFD,
+                (&mut pidfd as *mut libc::c_int).cast(),
+                &mut len,
+            )
+        };
+        if result == -1 {
+            let err = io::Error::last_os_error();
+            return Err(match err.raw_os_error() {
+                Some(libc::ENOPROTOOPT) => io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "SO_PEERPIDFD isn't supported by this kernel",
+                ),
+                _ => err,
+            });
+        }
+        // Safety: The kernel returned a new pidfd, which we now own.
+        Ok(unsafe { OwnedGrip::from_raw_fd(pidfd) })
+    }
+}
+
+#[cfg(any(
+    target_vendor = "apple",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+mod imp {
+    use super::PeerCredentials;
+    use crate::grip::AsGrip;
+    use crate::os::rustix::AsRawFd;
+    use std::io;
+
+    pub(super) fn peer_credentials<Socket: AsGrip>(socket: &Socket) -> io::Result<PeerCredentials> {
+        let fd = socket.as_grip().as_raw_fd();
+        let mut uid: libc::uid_t = 0;
+        let mut gid: libc::gid_t = 0;
+        if unsafe { libc::getpeereid(fd, &mut uid, &mut gid) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(PeerCredentials {
+            pid: peer_pid(fd),
+            uid,
+            gid,
+        })
+    }
+
+    #[cfg(target_vendor = "apple")]
+    fn peer_pid(fd: libc::c_int) -> Option<u32> {
+        let mut pid: libc::pid_t = 0;
+        let mut len = std::mem::size_of::<libc::pid_t>() as libc::socklen_t;
+        let result = unsafe {
+            libc::getsockopt(
+                fd,
+                libc::SOL_LOCAL,
+                libc::LOCAL_PEERPID,
+                (&mut pid as *mut libc::pid_t).cast(),
+                &mut len,
+            )
+        };
+        if result == -1 {
+            None
+        } else {
+            Some(pid as u32)
+        }
+    }
+
+    #[cfg(not(target_vendor = "apple"))]
+    fn peer_pid(_fd: libc::c_int) -> Option<u32> {
+        None
+    }
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "linux",
+    target_vendor = "apple",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+)))]
+mod imp {
+    use super::PeerCredentials;
+    use crate::grip::AsGrip;
+    use std::io;
+
+    pub(super) fn peer_credentials<Socket: AsGrip>(
+        _socket: &Socket,
+    ) -> io::Result<PeerCredentials> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "peer credentials aren't supported on this platform",
+        ))
+    }
+}
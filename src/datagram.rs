@@ -0,0 +1,375 @@
+//! `OwnedDatagram` and `BorrowedDatagram`, which preserve message
+//! boundaries.
+//!
+//! [`OwnedReadable`] and [`OwnedWriteable`] treat their grips as byte
+//! streams, which loses the boundaries between messages on UDP,
+//! `SOCK_DGRAM`, and `SOCK_SEQPACKET` sockets. These adapters send and
+//! receive whole messages, and report when a received message didn't fit in
+//! the buffer.
+//!
+//! [`OwnedReadable`]: crate::owned::OwnedReadable
+//! [`OwnedWriteable`]: crate::owned::OwnedWriteable
+
+use crate::addr::GripAddr;
+use crate::grip::{BorrowedGrip, OwnedGrip};
+use crate::passing::send_flags;
+use ::rustix::fs::{fstat, FileType};
+use ::rustix::net::{recvmsg, sendto, RecvAncillaryBuffer, RecvFlags, ReturnFlags};
+use io_lifetimes::{AsFd, BorrowedFd, OwnedFd};
+use std::io::{self, IoSliceMut};
+use std::net::UdpSocket;
+use std::os::unix::net::UnixDatagram;
+
+/// The result of receiving a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Received {
+    len: usize,
+    truncated: bool,
+}
+
+impl Received {
+    /// Returns the number of bytes written into the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the message was empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if the message was larger than the buffer, and the rest
+    /// of it was discarded.
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+/// An owned socket which sends and receives whole messages.
+///
+/// This can be constructed from any socket grip, with [`new`].
+///
+/// [`new`]: Self::new
+#[derive(Debug)]
+pub struct OwnedDatagram(OwnedFd);
+
+/// A borrowed socket which sends and receives whole messages.
+#[derive(Debug, Clone, Copy)]
+pub struct BorrowedDatagram<'a>(BorrowedFd<'a>);
+
+impl OwnedDatagram {
+    /// Creates an `OwnedDatagram` from a grip, failing with
+    /// [`io::ErrorKind::InvalidInput`] if it isn't a socket.
+    ///
+    /// On failure, the grip is closed.
+    pub fn new(grip: OwnedGrip) -> io::Result<Self> {
+        check_socket(grip.as_fd())?;
+        Ok(Self(grip))
+    }
+
+    /// Borrows this socket as a `BorrowedDatagram`.
+    #[inline]
+    pub fn as_datagram(&self) -> BorrowedDatagram<'_> {
+        BorrowedDatagram(self.0.as_fd())
+    }
+
+    /// Sends a message on a connected socket.
+    ///
+    /// See [`BorrowedDatagram::send`] for details.
+    #[inline]
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.as_datagram().send(buf)
+    }
+
+    /// Sends a message to `addr`.
+    ///
+    /// See [`BorrowedDatagram::send_to`] for details.
+    #[inline]
+    pub fn send_to(&self, buf: &[u8], addr: &GripAddr) -> io::Result<usize> {
+        self.as_datagram().send_to(buf, addr)
+    }
+
+    /// Receives a message.
+    ///
+    /// See [`BorrowedDatagram::recv`] for details.
+    #[inline]
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<Received> {
+        self.as_datagram().recv(buf)
+    }
+
+    /// Receives a message, and the address it was sent from.
+    ///
+    /// See [`BorrowedDatagram::recv_from`] for details.
+    #[inline]
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(Received, GripAddr)> {
+        self.as_datagram().recv_from(buf)
+    }
+
+    /// Sends several messages on a connected socket.
+    ///
+    /// See [`BorrowedDatagram::send_batch`] for details.
+    #[inline]
+    pub fn send_batch(&self, bufs: &[&[u8]]) -> io::Result<usize> {
+        self.as_datagram().send_batch(bufs)
+    }
+
+    /// Receives several messages.
+    ///
+    /// See [`BorrowedDatagram::recv_batch`] for details.
+    #[inline]
+    pub fn recv_batch(&self, bufs: &mut [&mut [u8]]) -> io::Result<Vec<Received>> {
+        self.as_datagram().recv_batch(bufs)
+    }
+}
+
+impl<'a> BorrowedDatagram<'a> {
+    /// Creates a `BorrowedDatagram` that sends and receives messages on a
+    /// socket `BorrowedGrip`, failing with [`io::ErrorKind::InvalidInput`] if
+    /// it isn't a socket.
+    #[inline]
+    pub fn borrow(grip: BorrowedGrip<'a>) -> io::Result<Self> {
+        check_socket(grip)?;
+        Ok(Self(grip))
+    }
+
+    /// Sends a message on a connected socket, returning the number of bytes
+    /// sent.
+    ///
+    /// Messages are sent whole, or not at all, so the result is always
+    /// `buf.len()`.
+    #[inline]
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        Ok(::rustix::net::send(self.0, buf, send_flags())?)
+    }
+
+    /// Sends a message to `addr`, returning the number of bytes sent.
+    #[inline]
+    pub fn send_to(&self, buf: &[u8], addr: &GripAddr) -> io::Result<usize> {
+        Ok(sendto(self.0, buf, send_flags(), &addr.to_rustix()?)?)
+    }
+
+    /// Receives a message into `buf`.
+    ///
+    /// If the message is larger than `buf`, the rest of it is discarded, and
+    /// [`Received::is_truncated`] returns true.
+    #[inline]
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<Received> {
+        self.recv_from(buf).map(|(received, _)| received)
+    }
+
+    /// Receives a message into `buf`, and returns the address it was sent
+    /// from.
+    ///
+    /// See [`recv`] for details.
+    ///
+    /// [`recv`]: Self::recv
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(Received, GripAddr)> {
+        let msg = recvmsg(
+            self.0,
+            &mut [IoSliceMut::new(buf)],
+            &mut RecvAncillaryBuffer::default(),
+            RecvFlags::empty(),
+        )?;
+        let received = Received {
+            len: msg.bytes,
+            truncated: msg.flags.contains(ReturnFlags::TRUNC),
+        };
+        Ok((received, GripAddr::from_rustix(msg.address)))
+    }
+
+    /// Sends each of `bufs` as a message on a connected socket, returning the
+    /// number of messages sent.
+    ///
+    /// If sending fails after some messages have been sent, this returns the
+    /// number sent so far, which is less than `bufs.len()`. On Linux and
+    /// Android this uses `sendmmsg`, to send them all with one system call.
+    #[inline]
+    pub fn send_batch(&self, bufs: &[&[u8]]) -> io::Result<usize> {
+        batch::send(self.0, bufs)
+    }
+
+    /// Receives messages into each of `bufs`, returning how each was
+    /// received.
+    ///
+    /// This waits for the first message, and then receives as many more as
+    /// are available without blocking, so it may return fewer than
+    /// `bufs.len()`. On Linux and Android this uses `recvmmsg`, to receive
+    /// them all with one system call.
+    #[inline]
+    pub fn recv_batch(&self, bufs: &mut [&mut [u8]]) -> io::Result<Vec<Received>> {
+        batch::recv(self.0, bufs)
+    }
+}
+
+impl From<UdpSocket> for OwnedDatagram {
+    #[inline]
+    fn from(socket: UdpSocket) -> Self {
+        Self(socket.into())
+    }
+}
+
+impl From<UnixDatagram> for OwnedDatagram {
+    #[inline]
+    fn from(socket: UnixDatagram) -> Self {
+        Self(socket.into())
+    }
+}
+
+impl From<OwnedDatagram> for OwnedFd {
+    #[inline]
+    fn from(datagram: OwnedDatagram) -> Self {
+        datagram.0
+    }
+}
+
+impl AsFd for OwnedDatagram {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+impl<'a> AsFd for BorrowedDatagram<'a> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0
+    }
+}
+
+/// Fails with [`io::ErrorKind::InvalidInput`] if `fd` isn't a socket.
+fn check_socket(fd: BorrowedFd<'_>) -> io::Result<()> {
+    if FileType::from_raw_mode(fstat(fd)?.st_mode as _) != FileType::Socket {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "grip isn't a socket",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod batch {
+    use super::{send_flags, Received};
+    use crate::os::rustix::AsRawFd;
+    use io_lifetimes::BorrowedFd;
+    use std::io;
+    use std::ptr::null_mut;
+
+    pub(super) fn send(fd: BorrowedFd<'_>, bufs: &[&[u8]]) -> io::Result<usize> {
+        let mut iovecs: Vec<libc::iovec> = bufs
+            .iter()
+            .map(|buf| libc::iovec {
+                iov_base: buf.as_ptr() as *mut libc::c_void,
+                iov_len: buf.len(),
+            })
+            .collect();
+        let mut msgs: Vec<libc::mmsghdr> = iovecs.iter_mut().map(mmsghdr).collect();
+        if msgs.is_empty() {
+            return Ok(0);
+        }
+
+        let n = unsafe {
+            libc::sendmmsg(
+                fd.as_raw_fd(),
+                msgs.as_mut_ptr(),
+                msgs.len() as _,
+                send_flags().bits() as _,
+            )
+        };
+        if n == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(n as usize)
+    }
+
+    pub(super) fn recv(fd: BorrowedFd<'_>, bufs: &mut [&mut [u8]]) -> io::Result<Vec<Received>> {
+        let mut iovecs: Vec<libc::iovec> = bufs
+            .iter_mut()
+            .map(|buf| libc::iovec {
+                iov_base: buf.as_mut_ptr().cast(),
+                iov_len: buf.len(),
+            })
+            .collect();
+        let mut msgs: Vec<libc::mmsghdr> = iovecs.iter_mut().map(mmsghdr).collect();
+        if msgs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let n = unsafe {
+            libc::recvmmsg(
+                fd.as_raw_fd(),
+                msgs.as_mut_ptr(),
+                msgs.len() as _,
+                libc::MSG_WAITFORONE as _,
+                null_mut(),
+            )
+        };
+        if n == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(msgs[..n as usize]
+            .iter()
+            .map(|msg| Received {
+                len: msg.msg_len as usize,
+                truncated: msg.msg_hdr.msg_flags & libc::MSG_TRUNC != 0,
+            })
+            .collect())
+    }
+
+    fn mmsghdr(iovec: &mut libc::iovec) -> libc::mmsghdr {
+        // Safety: `mmsghdr` is a plain C struct, for which zero is a valid
+        // value, and we then fill in the fields we need.
+        let mut msg: libc::mmsghdr = unsafe { std::mem::zeroed() };
+        msg.msg_hdr.msg_iov = iovec;
+        msg.msg_hdr.msg_iovlen = 1;
+        msg
+    }
+}
+
+/// Without `sendmmsg` and `recvmmsg`, send and receive one message at a
+/// time.
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+mod batch {
+    use super::{BorrowedDatagram, Received};
+    use ::rustix::net::{RecvAncillaryBuffer, RecvFlags, ReturnFlags};
+    use io_lifetimes::BorrowedFd;
+    use std::io::{self, IoSliceMut};
+
+    pub(super) fn send(fd: BorrowedFd<'_>, bufs: &[&[u8]]) -> io::Result<usize> {
+        let datagram = BorrowedDatagram(fd);
+        for (sent, buf) in bufs.iter().enumerate() {
+            if let Err(err) = datagram.send(buf) {
+                return if sent == 0 { Err(err) } else { Ok(sent) };
+            }
+        }
+        Ok(bufs.len())
+    }
+
+    pub(super) fn recv(fd: BorrowedFd<'_>, bufs: &mut [&mut [u8]]) -> io::Result<Vec<Received>> {
+        let mut received = Vec::new();
+        for buf in bufs {
+            let flags = if received.is_empty() {
+                RecvFlags::empty()
+            } else {
+                RecvFlags::DONTWAIT
+            };
+            match ::rustix::net::recvmsg(
+                fd,
+                &mut [IoSliceMut::new(buf)],
+                &mut RecvAncillaryBuffer::default(),
+                flags,
+            ) {
+                Ok(msg) => received.push(Received {
+                    len: msg.bytes,
+                    truncated: msg.flags.contains(ReturnFlags::TRUNC),
+                }),
+                Err(::rustix::io::Errno::WOULDBLOCK) if !received.is_empty() => break,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(received)
+    }
+}
@@ -0,0 +1,186 @@
+//! Opening command-line I/O locations, such as `-`, `fd:3`, or
+//! `tcp:localhost:8080`.
+//!
+//! An [`Endpoint`] is parsed from a string with the following syntax:
+//!
+//!  - `-`: stdin when reading, stdout when writing, and both when reading
+//!    and writing.
+//!  - `fd:N`: the grip inherited from the parent at number `N`, as taken by
+//!    [`take_grip_from_arg`].
+//!  - `unix:PATH`: a Unix-domain stream socket at `PATH`.
+//!  - `tcp:HOST:PORT`: a TCP socket. `HOST` may be a name, an IPv4 address,
+//!    or an IPv6 address in brackets, such as `tcp:[::1]:80`.
+//!  - `file:PATH`: the file at `PATH`. Use this for paths which would
+//!    otherwise look like one of the above.
+//!  - Anything else is a file path.
+//!
+//! Endpoints can then be opened for reading, writing, or both, and socket
+//! endpoints can be listened on.
+//!
+//! [`take_grip_from_arg`]: crate::inherit::take_grip_from_arg
+
+use crate::grip::{FromGrip, IntoGrip};
+use crate::inherit::take_raw_fd;
+use crate::listener::GripListener;
+use crate::os::rustix::RawFd;
+use crate::owned::{OwnedReadWrite, OwnedReadable, OwnedWriteable};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// A location to read from, write to, or listen on, parsed from a string.
+///
+/// See the [module documentation] for the syntax.
+///
+/// [module documentation]: self
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Endpoint {
+    /// `-`, for stdin and stdout.
+    Stdio,
+
+    /// `fd:N`, for a grip inherited from the parent process.
+    Fd(RawFd),
+
+    /// `unix:PATH`, for a Unix-domain stream socket.
+    Unix(PathBuf),
+
+    /// `tcp:HOST:PORT`, for a TCP socket.
+    Tcp(String),
+
+    /// `file:PATH`, or any other string, for a file.
+    File(PathBuf),
+}
+
+impl Endpoint {
+    /// Opens this endpoint for reading.
+    ///
+    /// Files are opened read-only, and sockets are connected.
+    pub fn open_readable(&self) -> io::Result<OwnedReadable> {
+        let grip = match self {
+            Self::Stdio => crate::grip::try_clone_to_owned(&io::stdin())?,
+            Self::Fd(raw_fd) => take_raw_fd(*raw_fd)?,
+            Self::Unix(path) => UnixStream::connect(path)?.into_grip(),
+            Self::Tcp(addr) => TcpStream::connect(addr.as_str())?.into_grip(),
+            Self::File(path) => File::open(path)?.into_grip(),
+        };
+        Ok(OwnedReadable::from_grip(grip))
+    }
+
+    /// Opens this endpoint for writing.
+    ///
+    /// Files are created if they don't exist, and truncated if they do, and
+    /// sockets are connected.
+    pub fn open_writeable(&self) -> io::Result<OwnedWriteable> {
+        let grip = match self {
+            Self::Stdio => crate::grip::try_clone_to_owned(&io::stdout())?,
+            Self::Fd(raw_fd) => take_raw_fd(*raw_fd)?,
+            Self::Unix(path) => UnixStream::connect(path)?.into_grip(),
+            Self::Tcp(addr) => TcpStream::connect(addr.as_str())?.into_grip(),
+            Self::File(path) => File::create(path)?.into_grip(),
+        };
+        Ok(OwnedWriteable::from_grip(grip))
+    }
+
+    /// Opens this endpoint for reading and writing.
+    ///
+    /// `-` reads from stdin and writes to stdout. Files are created if they
+    /// don't exist, and aren't truncated, and sockets are connected.
+    pub fn open_read_write(&self) -> io::Result<OwnedReadWrite> {
+        let grip = match self {
+            Self::Stdio => {
+                return Ok(OwnedReadWrite::from_pair(
+                    crate::grip::try_clone_to_owned(&io::stdin())?,
+                    crate::grip::try_clone_to_owned(&io::stdout())?,
+                ))
+            }
+            Self::Fd(raw_fd) => take_raw_fd(*raw_fd)?,
+            Self::Unix(path) => UnixStream::connect(path)?.into_grip(),
+            Self::Tcp(addr) => TcpStream::connect(addr.as_str())?.into_grip(),
+            Self::File(path) => OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(path)?
+                .into_grip(),
+        };
+        Ok(OwnedReadWrite::from_grip(grip))
+    }
+
+    /// Listens on this endpoint.
+    ///
+    /// Unix-domain sockets are bound to their path, which must not exist,
+    /// and TCP sockets are bound to their address. `fd:N` endpoints must be
+    /// listening sockets, such as one passed by a supervisor. `-` and files
+    /// can't be listened on.
+    pub fn listen(&self) -> io::Result<GripListener> {
+        match self {
+            Self::Fd(raw_fd) => GripListener::new(take_raw_fd(*raw_fd)?),
+            Self::Unix(path) => Ok(UnixListener::bind(path)?.into()),
+            Self::Tcp(addr) => Ok(TcpListener::bind(addr.as_str())?.into()),
+            Self::Stdio | Self::File(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("can't listen on {}", self),
+            )),
+        }
+    }
+}
+
+impl FromStr for Endpoint {
+    type Err = io::Error;
+
+    fn from_str(spec: &str) -> io::Result<Self> {
+        if spec == "-" {
+            return Ok(Self::Stdio);
+        }
+        if let Some(raw_fd) = spec.strip_prefix("fd:") {
+            return match raw_fd.parse() {
+                Ok(raw_fd) if raw_fd >= 0 => Ok(Self::Fd(raw_fd)),
+                _ => Err(invalid(spec, "expected a file descriptor number")),
+            };
+        }
+        if let Some(path) = spec.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err(invalid(spec, "expected a path"));
+            }
+            return Ok(Self::Unix(path.into()));
+        }
+        if let Some(addr) = spec.strip_prefix("tcp:") {
+            return match addr.rsplit_once(':') {
+                Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {
+                    Ok(Self::Tcp(addr.to_owned()))
+                }
+                _ => Err(invalid(spec, "expected HOST:PORT")),
+            };
+        }
+        let path = spec.strip_prefix("file:").unwrap_or(spec);
+        if path.is_empty() {
+            return Err(invalid(spec, "expected a path"));
+        }
+        Ok(Self::File(path.into()))
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stdio => write!(f, "-"),
+            Self::Fd(raw_fd) => write!(f, "fd:{}", raw_fd),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+            Self::Tcp(addr) => write!(f, "tcp:{}", addr),
+            Self::File(path) => write!(f, "file:{}", path.display()),
+        }
+    }
+}
+
+fn invalid(spec: &str, msg: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("invalid endpoint {:?}: {}", spec, msg),
+    )
+}
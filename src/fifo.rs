@@ -0,0 +1,158 @@
+//! Creating and opening FIFOs, also known as named pipes.
+//!
+//! Opening a FIFO normally blocks until the other end is opened, which
+//! deadlocks a process which opens both ends itself, such as a test. These
+//! functions open FIFOs with timeouts, and with keep-alive, so that a reader
+//! doesn't see end-of-file between one writer closing and the next opening.
+
+use crate::grip::FromGrip;
+use crate::owned::{OwnedReadable, OwnedWriteable};
+use ::rustix::event::{poll, PollFd, PollFlags, Timespec};
+use ::rustix::fs::{fstat, open, stat, FileType, Mode, OFlags, Stat};
+use ::rustix::io::{ioctl_fionbio, Errno};
+use io_lifetimes::OwnedFd;
+use std::ffi::CString;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// The longest interval between attempts to open a FIFO for writing.
+const MAX_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Creates a FIFO at `path`, with permissions `mode`, as modified by the
+/// process' umask.
+pub fn create<P: AsRef<Path>>(path: P, mode: u32) -> io::Result<()> {
+    let path = CString::new(path.as_ref().as_os_str().as_bytes())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    if unsafe { libc::mkfifo(path.as_ptr(), mode as libc::mode_t) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Opens the FIFO at `path` for reading, blocking until a writer opens it.
+///
+/// This fails with [`io::ErrorKind::InvalidInput`] if `path` isn't a FIFO.
+pub fn open_reader<P: AsRef<Path>>(path: P) -> io::Result<OwnedReadable> {
+    let fd = open_fifo(path.as_ref(), OFlags::RDONLY)?;
+    Ok(OwnedReadable::from_grip(fd))
+}
+
+/// Opens the FIFO at `path` for writing, blocking until a reader opens it.
+///
+/// This fails with [`io::ErrorKind::InvalidInput`] if `path` isn't a FIFO.
+pub fn open_writer<P: AsRef<Path>>(path: P) -> io::Result<OwnedWriteable> {
+    let fd = open_fifo(path.as_ref(), OFlags::WRONLY)?;
+    Ok(OwnedWriteable::from_grip(fd))
+}
+
+/// Opens the FIFO at `path` for reading, waiting up to `timeout` for a
+/// writer.
+///
+/// A reader can't tell when a writer opens the FIFO, so this waits until a
+/// writer writes data, or opens and closes the FIFO. If neither happens
+/// within `timeout`, this fails with [`io::ErrorKind::TimedOut`]. The
+/// returned reader is in blocking mode.
+pub fn open_reader_timeout<P: AsRef<Path>>(
+    path: P,
+    timeout: Duration,
+) -> io::Result<OwnedReadable> {
+    let fd = open_fifo(path.as_ref(), OFlags::RDONLY | OFlags::NONBLOCK)?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let remaining = Timespec::try_from(remaining).unwrap_or(Timespec {
+            tv_sec: i64::MAX,
+            tv_nsec: 0,
+        });
+        let mut fds = [PollFd::new(&fd, PollFlags::IN)];
+        match poll(&mut fds, Some(&remaining)) {
+            Ok(0) => return Err(timed_out(path.as_ref())),
+            Ok(_) => break,
+            Err(Errno::INTR) => continue,
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    ioctl_fionbio(&fd, false)?;
+    Ok(OwnedReadable::from_grip(fd))
+}
+
+/// Opens the FIFO at `path` for writing, waiting up to `timeout` for a
+/// reader.
+///
+/// If no reader opens the FIFO within `timeout`, this fails with
+/// [`io::ErrorKind::TimedOut`]. The returned writer is in blocking mode.
+pub fn open_writer_timeout<P: AsRef<Path>>(
+    path: P,
+    timeout: Duration,
+) -> io::Result<OwnedWriteable> {
+    // There's no way to wait for a reader, so retry with backoff. Opening
+    // for writing in nonblocking mode fails with `ENXIO` until there is one.
+    let deadline = Instant::now() + timeout;
+    let mut interval = Duration::from_millis(1);
+    let fd = loop {
+        match open_fifo(path.as_ref(), OFlags::WRONLY | OFlags::NONBLOCK) {
+            Err(err) if err.raw_os_error() == Some(libc::ENXIO) => {}
+            result => break result?,
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(timed_out(path.as_ref()));
+        }
+        sleep(interval.min(deadline - now));
+        interval = (interval * 2).min(MAX_RETRY_INTERVAL);
+    };
+
+    ioctl_fionbio(&fd, false)?;
+    Ok(OwnedWriteable::from_grip(fd))
+}
+
+/// Opens the FIFO at `path` for reading, holding it open for writing as
+/// well, without blocking.
+///
+/// Because this reader is also a writer, reads never see end-of-file, and
+/// instead wait for the next writer when the current one closes. This relies
+/// on opening the FIFO with `O_RDWR`, which POSIX leaves unspecified, and
+/// which Linux, the BSDs, and Apple platforms support.
+pub fn open_reader_keep_alive<P: AsRef<Path>>(path: P) -> io::Result<OwnedReadable> {
+    let fd = open_fifo(path.as_ref(), OFlags::RDWR)?;
+    Ok(OwnedReadable::from_grip(fd))
+}
+
+/// Opens `path` with `flags` and close-on-exec, checking that it's a FIFO.
+///
+/// `path` is checked before it's opened, since opening some other kinds of
+/// file, such as devices, has side effects, and checked again afterwards, in
+/// case it was replaced in between.
+fn open_fifo(path: &Path, flags: OFlags) -> io::Result<OwnedFd> {
+    if !is_fifo(&stat(path)?) {
+        return Err(not_fifo(path));
+    }
+    let fd = open(path, flags | OFlags::CLOEXEC, Mode::empty())?;
+    if !is_fifo(&fstat(&fd)?) {
+        return Err(not_fifo(path));
+    }
+    Ok(fd)
+}
+
+fn is_fifo(stat: &Stat) -> bool {
+    FileType::from_raw_mode(stat.st_mode as _) == FileType::Fifo
+}
+
+fn not_fifo(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("{} isn't a FIFO", path.display()),
+    )
+}
+
+fn timed_out(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("timed out waiting to open {}", path.display()),
+    )
+}
@@ -8,11 +8,31 @@ use crate::os::windows::{
     BorrowedHandleOrSocket, FromRawHandleOrSocket, IntoRawHandleOrSocket, OwnedHandleOrSocket,
     RawHandleOrSocket,
 };
+use io_lifetimes::AsSocketlike;
+use std::io;
+use std::net::{Shutdown, TcpStream};
+#[cfg(unix)]
+use std::time::Duration;
 #[cfg(not(windows))]
 use {
     crate::os::rustix::{AsRawFd, AsRawReadWriteFd, AsReadWriteFd, FromRawFd, IntoRawFd, RawFd},
     io_lifetimes::{AsFd, BorrowedFd, OwnedFd},
 };
+#[cfg(windows)]
+use {
+    io_lifetimes::AsFilelike,
+    std::fs::File,
+    std::io::Seek,
+    std::os::windows::io::{AsRawHandle, AsRawSocket},
+    std::ptr::null_mut,
+    windows_sys::Win32::Foundation::{CloseHandle, HANDLE},
+    windows_sys::Win32::Networking::WinSock::{
+        closesocket, ioctlsocket, setsockopt, WSAPoll, FIONREAD, LINGER, POLLHUP, POLLRDNORM,
+        SOCKET, SOL_SOCKET, SO_LINGER, WSAPOLLFD,
+    },
+    windows_sys::Win32::Storage::FileSystem::{GetFileType, FILE_TYPE_PIPE},
+    windows_sys::Win32::System::Pipes::PeekNamedPipe,
+};
 
 /// Portability abstraction over `BorrowedFd` and `BorrowedHandleOrSocket`.
 #[cfg(not(windows))]
@@ -383,3 +403,491 @@ pub unsafe fn borrow_raw<'a>(grip: RawGrip) -> BorrowedGrip<'a> {
 pub unsafe fn borrow_raw<'a>(grip: RawGrip) -> BorrowedGrip<'a> {
     BorrowedHandleOrSocket::borrow_raw(grip)
 }
+
+/// Returns the number of bytes that can currently be read from `grip`.
+///
+/// For regular files, this is the file size minus the current position. For
+/// pipes, sockets, and terminals, this is the number of bytes buffered and
+/// ready to be read without blocking.
+#[cfg(unix)]
+pub fn bytes_available<Grip: AsGrip>(grip: &Grip) -> io::Result<u64> {
+    use ::rustix::fs::{fstat, tell, FileType};
+
+    let grip = grip.as_grip();
+    let stat = fstat(grip)?;
+    if FileType::from_raw_mode(stat.st_mode) == FileType::RegularFile {
+        let pos = tell(grip)?;
+        return Ok((stat.st_size as u64).saturating_sub(pos));
+    }
+    Ok(::rustix::io::ioctl_fionread(grip)?)
+}
+
+/// Returns the number of bytes that can currently be read from `grip`.
+///
+/// For regular files, this is the file size minus the current position. For
+/// pipes and sockets, this is the number of bytes buffered and ready to be
+/// read without blocking. Other kinds of handles are not currently supported.
+#[cfg(windows)]
+pub fn bytes_available<Grip: AsGrip>(grip: &Grip) -> io::Result<u64> {
+    let grip = grip.as_grip();
+    if let Some(socket) = grip.as_socket() {
+        let mut len = 0_u32;
+        if unsafe { ioctlsocket(socket.as_raw_socket() as SOCKET, FIONREAD, &mut len) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        return Ok(len.into());
+    }
+    if let Some(handle) = grip.as_handle() {
+        let raw_handle = handle.as_raw_handle() as HANDLE;
+        if unsafe { GetFileType(raw_handle) } == FILE_TYPE_PIPE {
+            let mut len = 0_u32;
+            if unsafe { PeekNamedPipe(raw_handle, null_mut(), 0, null_mut(), &mut len, null_mut()) }
+                == 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+            return Ok(len.into());
+        }
+        let file = handle.as_filelike_view::<File>();
+        let metadata = file.metadata()?;
+        if metadata.is_file() {
+            let pos = (&*file).stream_position()?;
+            return Ok(metadata.len().saturating_sub(pos));
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "bytes_available is only supported on files, pipes, and sockets",
+    ))
+}
+
+/// Tests whether the other end of `grip` has hung up, without consuming any
+/// data.
+///
+/// This returns `true` if `grip` is a pipe whose other end has been closed,
+/// or a socket whose peer has closed or shut down its writing half. There may
+/// still be buffered data available to be read.
+#[cfg(unix)]
+pub fn is_peer_closed<Grip: AsGrip>(grip: &Grip) -> io::Result<bool> {
+    use ::rustix::event::{poll, PollFd, PollFlags, Timespec};
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    let hangup = PollFlags::HUP | PollFlags::RDHUP | PollFlags::ERR;
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    let hangup = PollFlags::HUP | PollFlags::ERR;
+
+    let mut fds = [PollFd::from_borrowed_fd(grip.as_grip(), hangup)];
+    let timeout = Timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    poll(&mut fds, Some(&timeout))?;
+    Ok(fds[0].revents().intersects(hangup))
+}
+
+/// Tests whether the other end of `grip` has hung up, without consuming any
+/// data.
+///
+/// This returns `true` if `grip` is a socket whose peer has disconnected.
+/// There may still be buffered data available to be read. Handles other than
+/// sockets are not currently supported.
+#[cfg(windows)]
+pub fn is_peer_closed<Grip: AsGrip>(grip: &Grip) -> io::Result<bool> {
+    let socket = grip.as_grip().as_socket().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "is_peer_closed is only supported on sockets",
+        )
+    })?;
+    let mut fd = WSAPOLLFD {
+        fd: socket.as_raw_socket() as SOCKET,
+        events: POLLRDNORM,
+        revents: 0,
+    };
+    if unsafe { WSAPoll(&mut fd, 1, 0) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(fd.revents & POLLHUP != 0)
+}
+
+/// Closes `grip`, reporting any error from the OS.
+///
+/// Dropping an `OwnedGrip` silently ignores errors from closing it. Some
+/// filesystems, such as NFS, report write errors when a file is closed, so
+/// code which needs to observe every failure should use this instead.
+///
+/// The grip is closed even if this returns an error, and it mustn't be
+/// closed again, since its number may already have been reused. If `close`
+/// is interrupted by a signal, Linux, Android, the BSDs, and Apple platforms
+/// have still released the file descriptor, so this treats `EINTR` as
+/// success there. On other platforms, POSIX leaves the state of the file
+/// descriptor unspecified, so this fails with
+/// [`io::ErrorKind::Interrupted`].
+#[cfg(unix)]
+pub fn close(grip: OwnedGrip) -> io::Result<()> {
+    match unsafe { ::rustix::io::try_close(grip.into_raw_fd()) } {
+        #[cfg(any(
+            target_os = "android",
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd",
+            target_vendor = "apple",
+        ))]
+        Err(::rustix::io::Errno::INTR) => Ok(()),
+        Ok(()) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Closes `grip`, reporting any error from the OS.
+///
+/// Dropping an `OwnedGrip` silently ignores errors from closing it. Some
+/// filesystems report write errors when a file is closed, so code which
+/// needs to observe every failure should use this instead.
+///
+/// The grip is closed even if this returns an error.
+#[cfg(windows)]
+pub fn close(grip: OwnedGrip) -> io::Result<()> {
+    let raw = grip.into_raw_handle_or_socket();
+    let failed = match raw.as_raw_socket() {
+        Some(raw_socket) => unsafe { closesocket(raw_socket as SOCKET) != 0 },
+        None => unsafe { CloseHandle(raw.as_raw_handle().unwrap() as HANDLE) == 0 },
+    };
+    if failed {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Closes the socket `grip` abortively, discarding any unsent data and
+/// resetting the connection, rather than shutting it down gracefully.
+///
+/// This sets `SO_LINGER` to zero before closing. This fails if `grip` isn't
+/// a socket, in which case it's closed normally.
+#[cfg(unix)]
+pub fn close_abortive(grip: OwnedGrip) -> io::Result<()> {
+    let linger = ::rustix::net::sockopt::set_socket_linger(&grip, Some(Duration::ZERO));
+    close(grip)?;
+    Ok(linger?)
+}
+
+/// Closes the socket `grip` abortively, discarding any unsent data and
+/// resetting the connection, rather than shutting it down gracefully.
+///
+/// This sets `SO_LINGER` to zero before closing. This fails if `grip` isn't
+/// a socket, in which case it's closed normally.
+#[cfg(windows)]
+pub fn close_abortive(grip: OwnedGrip) -> io::Result<()> {
+    let linger = match grip.as_socket() {
+        Some(socket) => {
+            let value = LINGER {
+                l_onoff: 1,
+                l_linger: 0,
+            };
+            let result = unsafe {
+                setsockopt(
+                    socket.as_raw_socket() as SOCKET,
+                    SOL_SOCKET,
+                    SO_LINGER,
+                    (&value as *const LINGER).cast(),
+                    std::mem::size_of::<LINGER>() as i32,
+                )
+            };
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        }
+        None => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "abortive close is only supported on sockets",
+        )),
+    };
+    close(grip)?;
+    linger
+}
+
+/// Shuts down the reading half, writing half, or both halves of the socket
+/// `grip`.
+///
+/// This fails if `grip` isn't a socket. To half-close a pipe, close its
+/// handle.
+#[cfg(not(windows))]
+#[inline]
+pub fn shutdown<Grip: AsGrip>(grip: &Grip, how: Shutdown) -> io::Result<()> {
+    grip.as_grip()
+        .as_socketlike_view::<TcpStream>()
+        .shutdown(how)
+}
+
+/// Shuts down the reading half, writing half, or both halves of the socket
+/// `grip`.
+///
+/// This fails if `grip` isn't a socket. To half-close a pipe, close its
+/// handle.
+#[cfg(windows)]
+#[inline]
+pub fn shutdown<Grip: AsGrip>(grip: &Grip, how: Shutdown) -> io::Result<()> {
+    match grip.as_grip().as_socket() {
+        Some(socket) => socket.as_socketlike_view::<TcpStream>().shutdown(how),
+        None => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "shutdown is only supported on sockets",
+        )),
+    }
+}
+
+/// Shuts down the reading half of `rw`, which may have separate handles for
+/// reading and writing.
+///
+/// If `rw` has a single socket, such as a [`TcpStream`], writing remains
+/// possible. Separate handles which aren't sockets, such as a pair of pipes,
+/// can't be half-closed through a borrow, so this fails for them; use
+/// [`OwnedReadWrite`], which closes the reading handle instead.
+///
+/// [`OwnedReadWrite`]: crate::owned::OwnedReadWrite
+#[inline]
+pub fn shutdown_read<RW: AsReadWriteGrip>(rw: &RW) -> io::Result<()> {
+    shutdown(&rw.as_read_grip(), Shutdown::Read)
+}
+
+/// Shuts down the writing half of `rw`, which may have separate handles for
+/// reading and writing.
+///
+/// If `rw` has a single socket, such as a [`TcpStream`], this sends the peer
+/// an end-of-stream while reading remains possible. Separate handles which
+/// aren't sockets, such as a pair of pipes, can't be half-closed through a
+/// borrow, so this fails for them; use [`OwnedReadWrite`], which closes the
+/// writing handle instead.
+///
+/// [`OwnedReadWrite`]: crate::owned::OwnedReadWrite
+#[inline]
+pub fn shutdown_write<RW: AsReadWriteGrip>(rw: &RW) -> io::Result<()> {
+    shutdown(&rw.as_write_grip(), Shutdown::Write)
+}
+
+/// Creates a new owned handle for the same underlying resource as `grip`.
+///
+/// On Posix-ish platforms, this duplicates the file descriptor with
+/// close-on-exec set. On Windows, this duplicates the handle or socket as
+/// non-inheritable.
+#[cfg(not(windows))]
+#[inline]
+pub fn try_clone_to_owned<Grip: AsGrip>(grip: &Grip) -> io::Result<OwnedGrip> {
+    grip.as_grip().try_clone_to_owned()
+}
+
+/// Creates a new owned handle for the same underlying resource as `grip`.
+///
+/// On Posix-ish platforms, this duplicates the file descriptor with
+/// close-on-exec set. On Windows, this duplicates the handle or socket as
+/// non-inheritable.
+#[cfg(windows)]
+#[inline]
+pub fn try_clone_to_owned<Grip: AsGrip>(grip: &Grip) -> io::Result<OwnedGrip> {
+    let grip = grip.as_grip();
+    match grip.as_socket() {
+        Some(socket) => Ok(OwnedHandleOrSocket::from_socket(
+            socket.try_clone_to_owned()?,
+        )),
+        None => Ok(OwnedHandleOrSocket::from_handle(
+            grip.as_handle().unwrap().try_clone_to_owned()?,
+        )),
+    }
+}
+
+/// Opens the object `grip` refers to again, creating a new open file
+/// description with its own file offset and status flags.
+///
+/// Duplicated handles share their file offset and status flags, such as
+/// `O_NONBLOCK`, so independent users can interfere with each other.
+/// Reopening avoids this, and `flags` may request a different access mode
+/// than the original, such as [`OFlags::RDONLY`]. `O_CLOEXEC` and
+/// `O_NOCTTY` are always added.
+///
+/// This works by opening `/proc/self/fd/N`, so it requires procfs. Reopening
+/// a pipe follows the same rules as opening a FIFO, so opening the reading
+/// end blocks if there are no writers, unless [`OFlags::NONBLOCK`] is given.
+///
+/// # Errors
+///
+/// Sockets, and anonymous objects such as eventfds and epoll instances,
+/// can't be reopened, and fail with [`io::ErrorKind::Unsupported`]. If
+/// `/proc/self/fd` isn't available, this fails with
+/// [`io::ErrorKind::NotFound`].
+///
+/// [`OFlags::RDONLY`]: crate::os::rustix::OFlags::RDONLY
+/// [`OFlags::NONBLOCK`]: crate::os::rustix::OFlags::NONBLOCK
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn reopen<Grip: AsGrip>(grip: &Grip, flags: ::rustix::fs::OFlags) -> io::Result<OwnedGrip> {
+    use ::rustix::fs::{fstat, open, FileType, Mode, OFlags};
+    use ::rustix::io::Errno;
+
+    let fd = grip.as_grip();
+    if FileType::from_raw_mode(fstat(fd)?.st_mode) == FileType::Socket {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "sockets can't be reopened",
+        ));
+    }
+
+    let path = format!("/proc/self/fd/{}", fd.as_raw_fd());
+    match open(
+        path,
+        flags | OFlags::CLOEXEC | OFlags::NOCTTY,
+        Mode::empty(),
+    ) {
+        Ok(owned) => Ok(owned),
+        Err(Errno::NXIO) => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "anonymous objects can't be reopened",
+        )),
+        Err(Errno::NOENT) => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "/proc/self/fd isn't available for reopening",
+        )),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Duplicates `grip` onto the file descriptor number `target`, returning an
+/// owned handle for `target`.
+///
+/// If `target` is already open, it's atomically replaced. If `cloexec` is
+/// true, the new file descriptor has close-on-exec set; otherwise it's
+/// inherited by child processes. This fails if `grip` is already `target`.
+///
+/// # Safety
+///
+/// If `target` is open, the caller must own it, and nothing else may use it
+/// afterward except through the returned handle.
+#[cfg(unix)]
+pub unsafe fn dup_to<Grip: AsGrip>(
+    grip: &Grip,
+    target: RawFd,
+    cloexec: bool,
+) -> io::Result<OwnedGrip> {
+    use ::rustix::io::{dup3, DupFlags};
+    use std::mem::ManuallyDrop;
+
+    let flags = if cloexec {
+        DupFlags::CLOEXEC
+    } else {
+        DupFlags::empty()
+    };
+
+    // `dup3` takes the target as an `OwnedFd`, though it may not be open
+    // yet. If it fails, forget it so that we don't close it.
+    let mut new = ManuallyDrop::new(OwnedFd::from_raw_fd(target));
+    dup3(grip.as_grip(), &mut new, flags)?;
+    Ok(ManuallyDrop::into_inner(new))
+}
+
+/// Moves `grip` to a file descriptor number greater than or equal to `min`,
+/// with close-on-exec set.
+///
+/// This is useful for keeping handles out of the way of low numbers, such as
+/// the stdio file descriptors, which other code expects to manage.
+#[cfg(unix)]
+#[inline]
+pub fn move_above(grip: OwnedGrip, min: RawFd) -> io::Result<OwnedGrip> {
+    Ok(::rustix::io::fcntl_dupfd_cloexec(&grip, min)?)
+}
+
+/// The kind of object a grip refers to, as reported by [`kind`].
+#[cfg(unix)]
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GripKind {
+    /// A regular file.
+    File,
+    /// A directory.
+    Directory,
+    /// A pipe or FIFO.
+    Pipe,
+    /// A character device, such as a terminal.
+    CharacterDevice,
+    /// A block device.
+    BlockDevice,
+    /// A TCP socket which is listening for connections.
+    TcpListener,
+    /// A TCP socket which isn't listening for connections.
+    TcpStream,
+    /// A UDP socket.
+    UdpSocket,
+    /// A Unix-domain stream socket which is listening for connections.
+    UnixListener,
+    /// A Unix-domain stream socket which isn't listening for connections.
+    UnixStream,
+    /// A Unix-domain datagram socket.
+    UnixDatagram,
+    /// A Unix-domain sequenced-packet socket.
+    UnixSeqpacket,
+    /// Some other kind of socket.
+    OtherSocket,
+    /// Some other kind of object, such as an eventfd.
+    Other,
+}
+
+/// Determines what kind of object `grip` refers to.
+#[cfg(unix)]
+pub fn kind<Grip: AsGrip>(grip: &Grip) -> io::Result<GripKind> {
+    use ::rustix::fs::{fstat, FileType};
+
+    let fd = grip.as_grip();
+    Ok(match FileType::from_raw_mode(fstat(fd)?.st_mode) {
+        FileType::RegularFile => GripKind::File,
+        FileType::Directory => GripKind::Directory,
+        FileType::Fifo => GripKind::Pipe,
+        FileType::CharacterDevice => GripKind::CharacterDevice,
+        FileType::BlockDevice => GripKind::BlockDevice,
+        FileType::Socket => socket_kind(fd)?,
+        _ => GripKind::Other,
+    })
+}
+
+#[cfg(unix)]
+fn socket_kind(fd: BorrowedFd<'_>) -> io::Result<GripKind> {
+    use ::rustix::net::{getsockname, sockopt, AddressFamily, SocketType};
+
+    let family = getsockname(fd)?.address_family();
+    let type_ = sockopt::socket_type(fd)?;
+    let inet = family == AddressFamily::INET || family == AddressFamily::INET6;
+    let unix = family == AddressFamily::UNIX;
+
+    Ok(if type_ == SocketType::STREAM && (inet || unix) {
+        match (inet, is_listening(fd)?) {
+            (true, true) => GripKind::TcpListener,
+            (true, false) => GripKind::TcpStream,
+            (false, true) => GripKind::UnixListener,
+            (false, false) => GripKind::UnixStream,
+        }
+    } else if type_ == SocketType::DGRAM && inet {
+        GripKind::UdpSocket
+    } else if type_ == SocketType::DGRAM && unix {
+        GripKind::UnixDatagram
+    } else if type_ == SocketType::SEQPACKET && unix {
+        GripKind::UnixSeqpacket
+    } else {
+        GripKind::OtherSocket
+    })
+}
+
+#[cfg(all(unix, not(any(target_os = "ios", target_os = "macos"))))]
+pub(crate) fn is_listening(fd: BorrowedFd<'_>) -> io::Result<bool> {
+    Ok(::rustix::net::sockopt::socket_acceptconn(fd)?)
+}
+
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+pub(crate) fn is_listening(fd: BorrowedFd<'_>) -> io::Result<bool> {
+    // These platforms don't implement `SO_ACCEPTCONN`, so assume that an
+    // unconnected stream socket is listening.
+    match ::rustix::net::getpeername(fd) {
+        Ok(_) => Ok(false),
+        Err(::rustix::io::Errno::NOTCONN) => Ok(true),
+        Err(err) => Err(err.into()),
+    }
+}
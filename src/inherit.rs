@@ -0,0 +1,146 @@
+//! Taking ownership of grips inherited from a parent process.
+//!
+//! Parents pass grips by leaving them open across `exec` and telling the
+//! child their numbers, with conventions like `--fd=5` or `MYTOOL_FD=7`.
+//! [`CommandGripExt::grip_arg`] and [`CommandGripExt::grip_env`] do this in
+//! the parent, and [`take_grip_from_arg`] and [`take_grip_from_env`] take
+//! ownership of the grips in the child.
+//!
+//! [`CommandGripExt::grip_arg`]: crate::command::CommandGripExt::grip_arg
+//! [`CommandGripExt::grip_env`]: crate::command::CommandGripExt::grip_env
+
+use crate::grip::OwnedGrip;
+use crate::os::rustix::{FromRawFd, RawFd};
+use ::rustix::io::{fcntl_getfd, fcntl_setfd, FdFlags};
+use io_lifetimes::BorrowedFd;
+use std::collections::HashSet;
+use std::env;
+use std::io;
+use std::sync::{Mutex, PoisonError};
+
+/// File descriptors which have been taken, so that each is only owned once.
+static CLAIMED: Mutex<Option<HashSet<RawFd>>> = Mutex::new(None);
+
+/// Takes ownership of the inherited grip whose number is in the environment
+/// variable `name`, and unsets the variable.
+///
+/// See [`take_grip_from_arg`] for the checks this performs. Like
+/// [`std::env::remove_var`], this shouldn't be called while other threads
+/// may be accessing the environment.
+pub fn take_grip_from_env(name: &str) -> io::Result<OwnedGrip> {
+    let value = env::var(name).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("can't take a grip from {}: {}", name, err),
+        )
+    })?;
+    let grip = take_grip_from_arg(&value)?;
+    env::remove_var(name);
+    Ok(grip)
+}
+
+/// Takes ownership of the inherited grip whose number is `arg`, such as the
+/// `5` in `--fd=5`.
+///
+/// This checks that the file descriptor is open, that it hasn't already
+/// been taken by this function, [`take_grip_from_env`], or
+/// [`take_activated_grips`], and that it doesn't have close-on-exec set.
+/// A file descriptor can only be inherited across `exec` with close-on-exec
+/// clear, and the standard library, rustix, and this crate set it on
+/// everything they open, so this rejects file descriptors which something
+/// else in this process owns. Stdin, stdout, and stderr can't be taken,
+/// since the standard library owns them. The grip has close-on-exec set, so
+/// that it isn't passed on to this process' own children.
+///
+/// [`take_activated_grips`]: crate::activation::take_activated_grips
+pub fn take_grip_from_arg(arg: &str) -> io::Result<OwnedGrip> {
+    let raw_fd: RawFd = arg.parse().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{:?} isn't a file descriptor number", arg),
+        )
+    })?;
+    take_raw_fd(raw_fd)
+}
+
+/// Takes ownership of the inherited file descriptor `raw_fd`, performing
+/// the checks described in [`take_grip_from_arg`].
+pub(crate) fn take_raw_fd(raw_fd: RawFd) -> io::Result<OwnedGrip> {
+    if raw_fd < 3 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("fd {} is a stdio stream, and can't be taken", raw_fd),
+        ));
+    }
+
+    claim(std::iter::once(raw_fd))?;
+
+    // Safety: The parent passed this file descriptor to us, it's open, it
+    // isn't owned elsewhere in this process, and `CLAIMED` ensures that we
+    // only take ownership of it once.
+    let grip = unsafe { OwnedGrip::from_raw_fd(raw_fd) };
+    if let Err(err) = fcntl_setfd(&grip, FdFlags::CLOEXEC) {
+        // Close it, and forget it, so that its number can be reused.
+        drop(grip);
+        unclaim(std::iter::once(raw_fd));
+        return Err(err.into());
+    }
+    Ok(grip)
+}
+
+/// Records that the inherited file descriptors `raw_fds` are being taken,
+/// failing if any of them have already been taken, are not open, or have
+/// close-on-exec set.
+pub(crate) fn claim(raw_fds: impl Iterator<Item = RawFd> + Clone) -> io::Result<()> {
+    let mut claimed = CLAIMED.lock().unwrap_or_else(PoisonError::into_inner);
+    let claimed = claimed.get_or_insert_with(HashSet::new);
+    for raw_fd in raw_fds.clone() {
+        if claimed.contains(&raw_fd) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("fd {} has already been taken", raw_fd),
+            ));
+        }
+        check_inherited(raw_fd)?;
+    }
+    claimed.extend(raw_fds);
+    Ok(())
+}
+
+/// Forgets that the file descriptors `raw_fds` were taken, after a failure
+/// part way through taking them, once any owners of them have been dropped.
+pub(crate) fn unclaim(raw_fds: impl Iterator<Item = RawFd>) {
+    let mut claimed = CLAIMED.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(claimed) = claimed.as_mut() {
+        for raw_fd in raw_fds {
+            claimed.remove(&raw_fd);
+        }
+    }
+}
+
+/// Checks that `raw_fd` is open, and doesn't have close-on-exec set.
+///
+/// Inherited file descriptors have close-on-exec clear, or they wouldn't
+/// have survived `exec`. Everything the standard library and rustix open has
+/// it set, so this rejects file descriptors which are owned elsewhere in
+/// this process, such as by a live `File`.
+fn check_inherited(raw_fd: RawFd) -> io::Result<()> {
+    // Safety: We only use this to test whether `raw_fd` is open, and to read
+    // its flags.
+    let fd = unsafe { BorrowedFd::borrow_raw(raw_fd) };
+    match fcntl_getfd(fd) {
+        Ok(flags) if flags.contains(FdFlags::CLOEXEC) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "fd {} has close-on-exec set, so it wasn't inherited, and may be owned \
+                 elsewhere in this process",
+                raw_fd
+            ),
+        )),
+        Ok(_) => Ok(()),
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("fd {} isn't open", raw_fd),
+        )),
+    }
+}
@@ -12,17 +12,113 @@
 //!   `BorrowedWriteable`, `RawReadable` and `RawWriteable`, which adapt a raw
 //!   `Fd`/`Handle` to implement the `Read` and `Write` traits, respectively.
 //!
+//! - `OwnedReadWrite`, which owns a socket, or a pair of handles such as
+//!   pipes, and implements both `Read` and `Write`, with half-close support.
+//!
+//! - `OwnedDatagram` and `BorrowedDatagram`, which send and receive whole
+//!   messages on datagram sockets, on Posix-ish platforms.
+//!
+//! - `Peekable`, which adapts any readable grip to support peeking at
+//!   input without consuming it.
+//!
+//! - Scoped redirection of stdin, stdout, and stderr to other grips, on
+//!   Posix-ish platforms.
+//!
+//! - `capture_stdio`, which captures fd-level stdio for tests, on Posix-ish
+//!   platforms.
+//!
+//! - `CommandGripExt`, which passes grips to child processes at chosen file
+//!   descriptor numbers, on Posix-ish platforms.
+//!
+//! - `GripListener`, which accepts connections on any listening socket, on
+//!   Posix-ish platforms.
+//!
+//! - `Endpoint`, which opens I/O locations given on the command line, such
+//!   as `-`, `fd:3`, or `tcp:localhost:8080`, on Posix-ish platforms.
+//!
+//! - FIFO helpers, which create FIFOs and open them with timeouts or
+//!   keep-alive, on Posix-ish platforms.
+//!
+//! - `memfd::create`, which creates anonymous memory-backed files which can
+//!   be sealed to make them immutable, on Linux, Android, and FreeBSD.
+//!
+//! - `Mapping`, which memory-maps files, and block devices on Linux and
+//!   Android, and a `read_to_end` which preallocates for regular files, on
+//!   Posix-ish platforms.
+//!
+//! - Pseudoterminals, for running child processes as if on a terminal, on
+//!   Posix-ish platforms.
+//!
+//! - Terminal helpers, such as `is_terminal` and `RawModeGuard`, which work
+//!   with any grip, on Posix-ish platforms.
+//!
+//! - `take_activated_grips`, which takes ownership of grips passed by
+//!   systemd socket activation, on Posix-ish platforms.
+//!
+//! - `take_grip_from_env` and `take_grip_from_arg`, which take ownership of
+//!   grips passed from a parent by number, on Posix-ish platforms.
+//!
+//! - `send_grips` and `recv_grips`, which pass grips between processes over
+//!   Unix-domain sockets, on Posix-ish platforms.
+//!
+//! - `peer_credentials`, which identifies the process on the other end of a
+//!   Unix-domain socket, on Posix-ish platforms.
+//!
+//! - `channel`, which creates typed channels whose messages carry grips, on
+//!   Posix-ish platforms with `SOCK_SEQPACKET` sockets.
+//!
+//! - Vectored I/O helpers, such as `write_all_vectored`, which work on stable
+//!   Rust.
+//!
 //! - `ReadWrite` traits, and supporting types, which provide abstractions over
 //!   types with one or two I/O resources, for reading and for writing.
 
 #![deny(missing_docs)]
 #![cfg_attr(can_vector, feature(can_vector))]
 #![cfg_attr(write_all_vectored, feature(write_all_vectored))]
+#![cfg_attr(read_buf, feature(read_buf, core_io_borrowed_buf))]
 #![cfg_attr(target_os = "wasi", feature(wasi_ext))]
 
+#[cfg(unix)]
+pub mod activation;
+#[cfg(unix)]
+pub mod addr;
 pub mod borrowed;
+#[cfg(unix)]
+pub mod capture;
+#[cfg(all(unix, not(target_vendor = "apple")))]
+pub mod channel;
+#[cfg(unix)]
+pub mod command;
+#[cfg(unix)]
+pub mod credentials;
+#[cfg(unix)]
+pub mod datagram;
+#[cfg(unix)]
+pub mod endpoint;
+#[cfg(unix)]
+pub mod fifo;
 pub mod grip;
+#[cfg(unix)]
+pub mod inherit;
+#[cfg(unix)]
+pub mod listener;
+#[cfg(any(target_os = "android", target_os = "linux", target_os = "freebsd"))]
+pub mod memfd;
+#[cfg(unix)]
+pub mod mmap;
 pub mod os;
 pub mod owned;
+#[cfg(unix)]
+pub mod passing;
+pub mod peek;
+#[cfg(unix)]
+pub mod pty;
 pub mod raw;
 pub mod read_write;
+#[cfg(unix)]
+pub mod redirect;
+pub mod sigpipe;
+#[cfg(unix)]
+pub mod terminal;
+pub mod vectored;
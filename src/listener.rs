@@ -0,0 +1,197 @@
+//! `GripListener`, which accepts connections on any listening socket.
+//!
+//! TCP listeners, Unix-domain listeners, and listeners passed in by a parent
+//! or by socket activation each have their own types and APIs.
+//! [`GripListener`] wraps any of them, so that one server loop can handle
+//! them all.
+
+use crate::addr::GripAddr;
+use crate::grip::{AsGrip, OwnedGrip};
+use crate::owned::OwnedReadWrite;
+use ::rustix::net::{acceptfrom_with, getsockname};
+use io_lifetimes::{AsFd, BorrowedFd, OwnedFd};
+use std::io;
+use std::net::TcpListener;
+use std::os::unix::net::UnixListener;
+
+/// A listening socket of any family.
+#[derive(Debug)]
+pub struct GripListener(OwnedFd);
+
+impl GripListener {
+    /// Creates a `GripListener` from a grip, failing with
+    /// [`io::ErrorKind::InvalidInput`] if it isn't a listening socket.
+    ///
+    /// On failure, the grip is closed.
+    pub fn new(grip: OwnedGrip) -> io::Result<Self> {
+        match crate::grip::is_listening(grip.as_grip()) {
+            Ok(true) => Ok(Self(grip)),
+            Ok(false) | Err(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "grip isn't a listening socket",
+            )),
+        }
+    }
+
+    /// Accepts a new connection, returning a blocking stream and the peer's
+    /// address.
+    ///
+    /// The stream has close-on-exec set. If this listener is in nonblocking
+    /// mode and there are no pending connections, this fails with
+    /// [`io::ErrorKind::WouldBlock`].
+    #[inline]
+    pub fn accept(&self) -> io::Result<(OwnedReadWrite, GripAddr)> {
+        self.accept_with(false)
+    }
+
+    /// Like [`accept`], but returns a stream in nonblocking mode, for use with
+    /// an event loop.
+    ///
+    /// [`accept`]: Self::accept
+    #[inline]
+    pub fn accept_nonblocking(&self) -> io::Result<(OwnedReadWrite, GripAddr)> {
+        self.accept_with(true)
+    }
+
+    fn accept_with(&self, nonblocking: bool) -> io::Result<(OwnedReadWrite, GripAddr)> {
+        let (stream, addr) = accept4(self.0.as_fd(), nonblocking)?;
+        Ok((
+            OwnedReadWrite::from_grip(stream),
+            GripAddr::from_rustix(addr),
+        ))
+    }
+
+    /// Returns an iterator over incoming connections, which calls [`accept`]
+    /// on each iteration, and never returns `None`.
+    ///
+    /// [`accept`]: Self::accept
+    #[inline]
+    pub fn incoming(&self) -> Incoming<'_> {
+        Incoming { listener: self }
+    }
+
+    /// Moves this listener into or out of nonblocking mode, which determines
+    /// whether [`accept`] waits for a connection.
+    ///
+    /// [`accept`]: Self::accept
+    #[inline]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        Ok(::rustix::io::ioctl_fionbio(&self.0, nonblocking)?)
+    }
+
+    /// Returns the address this listener is bound to.
+    #[inline]
+    pub fn local_addr(&self) -> io::Result<GripAddr> {
+        Ok(GripAddr::from_rustix(Some(getsockname(&self.0)?)))
+    }
+}
+
+/// An iterator over the connections to a [`GripListener`].
+///
+/// This is created by [`GripListener::incoming`].
+#[derive(Debug)]
+pub struct Incoming<'a> {
+    listener: &'a GripListener,
+}
+
+impl<'a> Iterator for Incoming<'a> {
+    type Item = io::Result<(OwnedReadWrite, GripAddr)>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.listener.accept())
+    }
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+fn accept4(
+    fd: BorrowedFd<'_>,
+    nonblocking: bool,
+) -> io::Result<(OwnedFd, Option<::rustix::net::SocketAddrAny>)> {
+    use ::rustix::net::SocketFlags;
+
+    let mut flags = SocketFlags::CLOEXEC;
+    if nonblocking {
+        flags |= SocketFlags::NONBLOCK;
+    }
+    Ok(acceptfrom_with(fd, flags)?)
+}
+
+/// These platforms lack `accept4`, so set the flags after accepting. A
+/// concurrent `fork` and `exec` may leak the stream into a child.
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+fn accept4(
+    fd: BorrowedFd<'_>,
+    nonblocking: bool,
+) -> io::Result<(OwnedFd, Option<::rustix::net::SocketAddrAny>)> {
+    use ::rustix::io::{fcntl_setfd, ioctl_fionbio, FdFlags};
+    use ::rustix::net::SocketFlags;
+
+    let (stream, addr) = acceptfrom_with(fd, SocketFlags::empty())?;
+    fcntl_setfd(&stream, FdFlags::CLOEXEC)?;
+    // Accepted sockets inherit the listener's mode on these platforms.
+    ioctl_fionbio(&stream, nonblocking)?;
+    Ok((stream, addr))
+}
+
+impl From<TcpListener> for GripListener {
+    #[inline]
+    fn from(listener: TcpListener) -> Self {
+        Self(listener.into())
+    }
+}
+
+impl From<UnixListener> for GripListener {
+    #[inline]
+    fn from(listener: UnixListener) -> Self {
+        Self(listener.into())
+    }
+}
+
+impl From<GripListener> for OwnedFd {
+    #[inline]
+    fn from(listener: GripListener) -> Self {
+        listener.0
+    }
+}
+
+impl AsFd for GripListener {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+/// Registers the listener with a mio `Poll`, to be notified when
+/// connections are ready to accept. The listener should be in nonblocking
+/// mode.
+#[cfg(feature = "use_mio_os_ext")]
+impl mio::event::Source for GripListener {
+    #[inline]
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        use crate::os::rustix::AsRawFd;
+        mio::unix::SourceFd(&self.0.as_raw_fd()).register(registry, token, interests)
+    }
+
+    #[inline]
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        use crate::os::rustix::AsRawFd;
+        mio::unix::SourceFd(&self.0.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    #[inline]
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        use crate::os::rustix::AsRawFd;
+        mio::unix::SourceFd(&self.0.as_raw_fd()).deregister(registry)
+    }
+}
@@ -0,0 +1,53 @@
+//! Anonymous memory-backed files, with sealing.
+//!
+//! [`create`] returns a grip for a file which lives in memory and has no
+//! name in the filesystem. It can be read and written with any of the
+//! adapters in this crate, and passed to child processes like any other
+//! grip. Once its contents are in place, [`add_seals`] can make it
+//! immutable, so that a process it's handed to can rely on that.
+
+use crate::grip::{AsGrip, OwnedGrip};
+use crate::os::rustix::{MemfdFlags, SealFlags};
+use ::rustix::fs::{fcntl_add_seals, fcntl_get_seals, memfd_create};
+use std::ffi::CString;
+use std::io;
+
+/// The seals which make a file immutable: [`SealFlags::WRITE`],
+/// [`SealFlags::SHRINK`], [`SealFlags::GROW`], and [`SealFlags::SEAL`].
+pub const IMMUTABLE: SealFlags = SealFlags::WRITE
+    .union(SealFlags::SHRINK)
+    .union(SealFlags::GROW)
+    .union(SealFlags::SEAL);
+
+/// Creates an empty anonymous file, with close-on-exec set, and returns a
+/// grip for reading and writing it.
+///
+/// `name` is only used for debugging, such as in `/proc/self/fd` on Linux,
+/// and needn't be unique. Without [`MemfdFlags::ALLOW_SEALING`], the file is
+/// created with [`SealFlags::SEAL`], so no seals can be added to it.
+/// [`MemfdFlags::CLOEXEC`] is always added to `flags`.
+pub fn create(name: &str, flags: MemfdFlags) -> io::Result<OwnedGrip> {
+    let name =
+        CString::new(name).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    Ok(memfd_create(name.as_c_str(), flags | MemfdFlags::CLOEXEC)?)
+}
+
+/// Adds `seals` to the file `grip`.
+///
+/// This fails with [`io::ErrorKind::PermissionDenied`] if the file has
+/// [`SealFlags::SEAL`], or wasn't created with
+/// [`MemfdFlags::ALLOW_SEALING`]. Adding [`SealFlags::WRITE`] fails with
+/// `EBUSY` while the file has shared, writable memory mappings.
+#[inline]
+pub fn add_seals<Grip: AsGrip + ?Sized>(grip: &Grip, seals: SealFlags) -> io::Result<()> {
+    Ok(fcntl_add_seals(grip.as_grip(), seals)?)
+}
+
+/// Returns the seals on the file `grip`.
+///
+/// This fails with [`io::ErrorKind::InvalidInput`] if `grip` isn't a file
+/// which supports sealing.
+#[inline]
+pub fn seals<Grip: AsGrip + ?Sized>(grip: &Grip) -> io::Result<SealFlags> {
+    Ok(fcntl_get_seals(grip.as_grip())?)
+}
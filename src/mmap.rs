@@ -0,0 +1,283 @@
+//! Memory-mapping files through grips, and reading regular files quickly.
+//!
+//! A [`Mapping`] maps the whole of a regular-file or block-device grip, such
+//! as a file or a memfd, into memory. It borrows the grip it's created from,
+//! so the grip can't be closed while the mapping exists.
+
+use crate::borrowed::BorrowedReadable;
+use crate::grip::{AsGrip, BorrowedGrip};
+use ::rustix::fs::{fstat, tell, FileType};
+use ::rustix::mm::{madvise, mmap, msync, munmap, MapFlags, MsyncFlags, ProtFlags};
+use std::ffi::c_void;
+use std::io::{self, Read};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr::{self, NonNull};
+use std::slice;
+
+/// How a [`Mapping`] may be accessed, and whether changes reach the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Mode {
+    /// The mapping may only be read. The grip must be readable.
+    ReadOnly,
+
+    /// The mapping may be written, but changes are private to the mapping,
+    /// and don't reach the file. The grip must be readable.
+    Private,
+
+    /// The mapping may be written, and changes reach the file, and other
+    /// shared mappings of it. The grip must be readable and writeable.
+    Shared,
+}
+
+/// A hint to the operating system about how a [`Mapping`] will be
+/// accessed, for use with [`Mapping::advise`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Advice {
+    /// No particular pattern. This is the default.
+    Normal,
+
+    /// Pages will be accessed in random order, so reading ahead is wasted.
+    Random,
+
+    /// Pages will be accessed in order, so reading ahead aggressively helps.
+    Sequential,
+
+    /// Pages will be accessed soon, so they should be read in now.
+    WillNeed,
+}
+
+/// A memory mapping of the contents of a grip.
+///
+/// The mapping is unmapped when this is dropped.
+#[derive(Debug)]
+pub struct Mapping<'a> {
+    ptr: NonNull<u8>,
+    len: usize,
+    mode: Mode,
+    _grip: PhantomData<BorrowedGrip<'a>>,
+}
+
+// Safety: `Mapping` owns its memory, like a `Box<[u8]>`.
+unsafe impl Send for Mapping<'_> {}
+unsafe impl Sync for Mapping<'_> {}
+
+impl<'a> Mapping<'a> {
+    /// Maps the whole of `grip`, which must be a regular file, such as a file
+    /// opened from the filesystem or a memfd, or on Linux and Android, a
+    /// block device, with the given `mode`.
+    ///
+    /// Other seekable files can't be mapped, since there's no way to find
+    /// their length without moving their position. This fails with
+    /// [`io::ErrorKind::InvalidInput`] for them, and for unseekable files
+    /// such as pipes.
+    ///
+    /// The length of the mapping is the length of the file when it's
+    /// mapped. An empty file is mapped as an empty slice.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while it's mapped, by this
+    /// process or any other, other than through this mapping, whatever the
+    /// `mode`. Truncating it makes accessing the mapping crash the process,
+    /// and modifying it changes memory that Rust assumes is immutable while
+    /// it's borrowed. This includes [`Mode::Private`] mappings, whose pages
+    /// show changes to the file until they're first written. A memfd sealed
+    /// with `memfd::IMMUTABLE` meets these requirements.
+    pub unsafe fn new<Grip: AsGrip + ?Sized>(grip: &'a Grip, mode: Mode) -> io::Result<Self> {
+        let grip = grip.as_grip();
+        let len = usize::try_from(file_len(grip)?)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "file is too large to map"))?;
+        if len == 0 {
+            // `mmap` rejects empty mappings, and there's nothing to map.
+            return Ok(Self {
+                ptr: NonNull::dangling(),
+                len,
+                mode,
+                _grip: PhantomData,
+            });
+        }
+
+        let (prot, flags) = match mode {
+            Mode::ReadOnly => (ProtFlags::READ, MapFlags::SHARED),
+            Mode::Private => (ProtFlags::READ | ProtFlags::WRITE, MapFlags::PRIVATE),
+            Mode::Shared => (ProtFlags::READ | ProtFlags::WRITE, MapFlags::SHARED),
+        };
+        let ptr = mmap(ptr::null_mut(), len, prot, flags, grip, 0)?;
+        Ok(Self {
+            ptr: NonNull::new(ptr.cast()).unwrap(),
+            len,
+            mode,
+            _grip: PhantomData,
+        })
+    }
+
+    /// Returns the mapped memory.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        // Safety: `ptr` points to `len` readable bytes, or is dangling and
+        // `len` is zero.
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Returns the mapped memory for writing, or `None` if this mapping is
+    /// [`Mode::ReadOnly`].
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        if self.mode == Mode::ReadOnly {
+            return None;
+        }
+        // Safety: `ptr` points to `len` writeable bytes, or is dangling and
+        // `len` is zero, and `&mut self` guarantees exclusive access.
+        Some(unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) })
+    }
+
+    /// Returns the length of the mapping, in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Tests whether the mapping is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the mode the mapping was created with.
+    #[inline]
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Hints to the operating system how the mapping will be accessed, with
+    /// `madvise`.
+    pub fn advise(&self, advice: Advice) -> io::Result<()> {
+        if self.len == 0 {
+            return Ok(());
+        }
+        let advice = match advice {
+            Advice::Normal => ::rustix::mm::Advice::Normal,
+            Advice::Random => ::rustix::mm::Advice::Random,
+            Advice::Sequential => ::rustix::mm::Advice::Sequential,
+            Advice::WillNeed => ::rustix::mm::Advice::WillNeed,
+        };
+        // Safety: These hints don't change the contents of the mapping.
+        Ok(unsafe { madvise(self.as_mut_ptr(), self.len, advice) }?)
+    }
+
+    /// Writes changes to a [`Mode::Shared`] mapping to the file, with
+    /// `msync`, waiting until they're written.
+    ///
+    /// For other modes, this does nothing.
+    #[inline]
+    pub fn flush(&self) -> io::Result<()> {
+        self.sync(MsyncFlags::SYNC)
+    }
+
+    /// Like [`flush`], but starts writing changes and returns without
+    /// waiting for them to be written.
+    ///
+    /// [`flush`]: Self::flush
+    #[inline]
+    pub fn flush_async(&self) -> io::Result<()> {
+        self.sync(MsyncFlags::ASYNC)
+    }
+
+    fn sync(&self, flags: MsyncFlags) -> io::Result<()> {
+        if self.mode != Mode::Shared || self.len == 0 {
+            return Ok(());
+        }
+        // Safety: `ptr` and `len` describe a mapping that we own.
+        Ok(unsafe { msync(self.as_mut_ptr(), self.len, flags) }?)
+    }
+
+    #[inline]
+    fn as_mut_ptr(&self) -> *mut c_void {
+        self.ptr.as_ptr().cast()
+    }
+}
+
+impl Drop for Mapping<'_> {
+    fn drop(&mut self) {
+        if self.len != 0 {
+            // Safety: `ptr` and `len` describe a mapping that we own, and no
+            // borrows of it outlive `self`.
+            let _ = unsafe { munmap(self.as_mut_ptr(), self.len) };
+        }
+    }
+}
+
+impl Deref for Mapping<'_> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for Mapping<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+/// Returns the length of the file `grip`, which must be a regular file, or
+/// on Linux and Android, a block device.
+///
+/// This uses `fstat`, or `ioctl` for block devices, rather than seeking, so
+/// that it doesn't change the position of the file, which is shared with
+/// other grips for it.
+fn file_len(grip: BorrowedGrip<'_>) -> io::Result<u64> {
+    let stat = fstat(grip)?;
+    match FileType::from_raw_mode(stat.st_mode as _) {
+        FileType::RegularFile => Ok(stat.st_size as u64),
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        FileType::BlockDevice => block_device_len(grip),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "only regular files and block devices can be mapped",
+        )),
+    }
+}
+
+/// Returns the length of the block device `grip`, with `BLKGETSIZE64`.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn block_device_len(grip: BorrowedGrip<'_>) -> io::Result<u64> {
+    use ::rustix::ioctl::{ioctl, opcode, Getter, Opcode};
+
+    // Linux declares this with `size_t`, but it gets a `u64`.
+    const BLKGETSIZE64: Opcode = opcode::read::<usize>(0x12, 114);
+
+    // Safety: `BLKGETSIZE64` is a getter opcode that gets a `u64`.
+    Ok(unsafe { ioctl(grip, Getter::<BLKGETSIZE64, u64>::new()) }?)
+}
+
+/// Reads all bytes from the current position of `grip` until end-of-file,
+/// appending them to `buf`, like [`Read::read_to_end`].
+///
+/// For regular files, this first reserves enough space in `buf` for the
+/// rest of the file, as reported by `fstat`, so that it reads with few,
+/// large, reads, and never reallocates unless the file grows. Other files
+/// are read as they are by [`Read::read_to_end`]. This reads rather than
+/// copying from a memory mapping, because a concurrent truncation of a
+/// mapped file would crash the process.
+pub fn read_to_end<Grip: AsGrip>(grip: Grip, buf: &mut Vec<u8>) -> io::Result<usize> {
+    let grip = grip.as_grip();
+    if let Ok(stat) = fstat(grip) {
+        if FileType::from_raw_mode(stat.st_mode as _) == FileType::RegularFile {
+            let pos = tell(grip)?;
+            let remaining = (stat.st_size as u64).saturating_sub(pos);
+            // Reserve one extra byte, so that detecting end-of-file doesn't
+            // force a reallocation.
+            if let Ok(remaining) = usize::try_from(remaining) {
+                buf.reserve(remaining.saturating_add(1));
+            }
+        }
+    }
+    BorrowedReadable::borrow(grip).read_to_end(buf)
+}
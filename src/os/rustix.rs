@@ -8,6 +8,31 @@ pub use std::os::wasi::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 
 pub use crate::read_write::{AsRawReadWriteFd, AsReadWriteFd};
 
+/// Flags for [`RawReadable::recv_with_flags`] and related functions.
+///
+/// [`RawReadable::recv_with_flags`]: crate::raw::RawReadable::recv_with_flags
+#[cfg(unix)]
+pub use ::rustix::net::RecvFlags;
+
+/// Flags for [`grip::reopen`].
+///
+/// [`grip::reopen`]: crate::grip::reopen
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub use ::rustix::fs::OFlags;
+
+/// Flags for [`memfd::create`].
+///
+/// [`memfd::create`]: crate::memfd::create
+#[cfg(any(target_os = "android", target_os = "linux", target_os = "freebsd"))]
+pub use ::rustix::fs::MemfdFlags;
+
+/// Seals for [`memfd::add_seals`] and [`memfd::seals`].
+///
+/// [`memfd::add_seals`]: crate::memfd::add_seals
+/// [`memfd::seals`]: crate::memfd::seals
+#[cfg(any(target_os = "android", target_os = "linux", target_os = "freebsd"))]
+pub use ::rustix::fs::SealFlags;
+
 // In theory we could do something similar for
 // `std::os::fortanix_sgx::io::{AsRawFd, FromRawFd, RawFd}`, however it lacks
 // `IntoRawFd`, and `std::fs::File` doesn't implement its `AsRawFd`, so it
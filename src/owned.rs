@@ -1,11 +1,21 @@
 //! `OwnedReadable` and `OwnedWriteable`.
 
-use crate::grip::{AsRawGrip, FromRawGrip, OwnedGrip};
+use crate::borrowed::{BorrowedReadable, BorrowedWriteable};
+use crate::grip::{AsGrip, AsRawGrip, BorrowedGrip, FromGrip, FromRawGrip, OwnedGrip};
 use crate::raw::{RawReadable, RawWriteable};
+use crate::sigpipe::NoSigpipe;
+#[cfg(unix)]
+use ::rustix::net::RecvFlags;
+#[cfg(feature = "bytes")]
+use bytes::BytesMut;
 #[cfg(not(windows))]
 use io_lifetimes::{AsFd, BorrowedFd, OwnedFd};
 use std::fmt;
+#[cfg(read_buf)]
+use std::io::BorrowedCursor;
 use std::io::{self, IoSlice, IoSliceMut, Read, Write};
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::net::Shutdown;
 #[cfg(all(doc, not(windows)))]
 use std::net::TcpStream;
 #[cfg(unix)]
@@ -46,6 +56,430 @@ pub struct OwnedReadable(RawReadable);
 #[repr(transparent)]
 pub struct OwnedWriteable(RawWriteable);
 
+/// An owning I/O handle, or pair of handles, that implements both [`Read`]
+/// and [`Write`].
+///
+/// This holds either a single handle used in both directions, such as a
+/// socket, or separate handles for reading and writing, such as the pipes
+/// connected to a child process' stdout and stdin.
+///
+/// This doesn't implement `Into*` or `From*` traits.
+pub struct OwnedReadWrite(Duplex);
+
+enum Duplex {
+    Single(OwnedGrip),
+    Pair {
+        read: Option<OwnedGrip>,
+        write: Option<OwnedGrip>,
+    },
+}
+
+impl OwnedReadWrite {
+    /// Creates an `OwnedReadWrite` which reads from and writes to `grip`.
+    #[inline]
+    pub fn from_grip(grip: OwnedGrip) -> Self {
+        Self(Duplex::Single(grip))
+    }
+
+    /// Creates an `OwnedReadWrite` which reads from `read` and writes to
+    /// `write`.
+    #[inline]
+    pub fn from_pair(read: OwnedGrip, write: OwnedGrip) -> Self {
+        Self(Duplex::Pair {
+            read: Some(read),
+            write: Some(write),
+        })
+    }
+
+    /// Creates a new `OwnedReadWrite` for the same underlying handles.
+    ///
+    /// Halves which have been closed by [`shutdown_read`] or
+    /// [`shutdown_write`] remain closed in the clone. See
+    /// [`grip::try_clone_to_owned`] for details.
+    ///
+    /// [`shutdown_read`]: Self::shutdown_read
+    /// [`shutdown_write`]: Self::shutdown_write
+    /// [`grip::try_clone_to_owned`]: crate::grip::try_clone_to_owned
+    #[inline]
+    pub fn try_clone(&self) -> io::Result<Self> {
+        let clone = |grip: &OwnedGrip| crate::grip::try_clone_to_owned(grip);
+        Ok(Self(match &self.0 {
+            Duplex::Single(grip) => Duplex::Single(clone(grip)?),
+            Duplex::Pair { read, write } => Duplex::Pair {
+                read: read.as_ref().map(clone).transpose()?,
+                write: write.as_ref().map(clone).transpose()?,
+            },
+        }))
+    }
+
+    /// Returns the handle used for reading, or `None` if it has been closed
+    /// by [`shutdown_read`].
+    ///
+    /// [`shutdown_read`]: Self::shutdown_read
+    #[inline]
+    pub fn read_grip(&self) -> Option<BorrowedGrip<'_>> {
+        match &self.0 {
+            Duplex::Single(grip) => Some(grip.as_grip()),
+            Duplex::Pair { read, .. } => read.as_ref().map(AsGrip::as_grip),
+        }
+    }
+
+    /// Returns the handle used for writing, or `None` if it has been closed
+    /// by [`shutdown_write`].
+    ///
+    /// [`shutdown_write`]: Self::shutdown_write
+    #[inline]
+    pub fn write_grip(&self) -> Option<BorrowedGrip<'_>> {
+        match &self.0 {
+            Duplex::Single(grip) => Some(grip.as_grip()),
+            Duplex::Pair { write, .. } => write.as_ref().map(AsGrip::as_grip),
+        }
+    }
+
+    /// Shuts down the reading half.
+    ///
+    /// With a single handle, this shuts down the reading half of the socket,
+    /// and fails if it isn't a socket. With separate handles, this closes the
+    /// reading handle, and subsequent reads return end-of-stream.
+    #[inline]
+    pub fn shutdown_read(&mut self) -> io::Result<()> {
+        match &mut self.0 {
+            Duplex::Single(grip) => crate::grip::shutdown(grip, Shutdown::Read),
+            Duplex::Pair { read, .. } => read.take().map_or(Ok(()), close_half),
+        }
+    }
+
+    /// Shuts down the writing half, so that the peer sees end-of-stream.
+    ///
+    /// With a single handle, this shuts down the writing half of the socket,
+    /// and fails if it isn't a socket. With separate handles, this closes the
+    /// writing handle, and subsequent writes fail with
+    /// [`io::ErrorKind::BrokenPipe`]. If the writing handle is a socket, it's
+    /// shut down before it's closed, so that the peer sees end-of-stream even
+    /// if the reading handle is a duplicate of the same socket.
+    #[inline]
+    pub fn shutdown_write(&mut self) -> io::Result<()> {
+        match &mut self.0 {
+            Duplex::Single(grip) => crate::grip::shutdown(grip, Shutdown::Write),
+            Duplex::Pair { write, .. } => write.take().map_or(Ok(()), close_write_half),
+        }
+    }
+
+    fn reader(&self) -> Option<BorrowedReadable<'_>> {
+        self.read_grip().map(BorrowedReadable::borrow)
+    }
+
+    fn writer(&self) -> io::Result<BorrowedWriteable<'_>> {
+        match self.write_grip() {
+            Some(grip) => Ok(BorrowedWriteable::borrow(grip)),
+            None => Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "the writing half has been shut down",
+            )),
+        }
+    }
+}
+
+#[cfg(any(unix, windows))]
+#[inline]
+fn close_half(grip: OwnedGrip) -> io::Result<()> {
+    crate::grip::close(grip)
+}
+
+#[cfg(not(any(unix, windows)))]
+#[inline]
+fn close_half(grip: OwnedGrip) -> io::Result<()> {
+    drop(grip);
+    Ok(())
+}
+
+/// Closes the writing handle of a pair, shutting it down first if it's a
+/// socket, since closing only sends end-of-stream once no other handles,
+/// such as a duplicate used for reading, refer to the socket.
+#[cfg(any(unix, windows))]
+fn close_write_half(grip: OwnedGrip) -> io::Result<()> {
+    match crate::grip::shutdown(&grip, Shutdown::Write) {
+        Ok(()) => {}
+        Err(err) if is_not_socket(&err) => {}
+        Err(err) => return Err(err),
+    }
+    close_half(grip)
+}
+
+#[cfg(not(any(unix, windows)))]
+#[inline]
+fn close_write_half(grip: OwnedGrip) -> io::Result<()> {
+    close_half(grip)
+}
+
+#[cfg(unix)]
+#[inline]
+fn is_not_socket(err: &io::Error) -> bool {
+    err.raw_os_error() == Some(libc::ENOTSOCK)
+}
+
+#[cfg(windows)]
+#[inline]
+fn is_not_socket(err: &io::Error) -> bool {
+    // `grip::shutdown` reports this for handles which aren't sockets.
+    err.kind() == io::ErrorKind::Unsupported
+}
+
+impl Read for OwnedReadWrite {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.reader() {
+            Some(mut reader) => reader.read(buf),
+            None => Ok(0),
+        }
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        match self.reader() {
+            Some(mut reader) => reader.read_vectored(bufs),
+            None => Ok(0),
+        }
+    }
+}
+
+impl Write for OwnedReadWrite {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer()?.write(buf)
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.writer()?.write_vectored(bufs)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        match self.write_grip() {
+            Some(grip) => BorrowedWriteable::borrow(grip).flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for OwnedReadWrite {
+    #[allow(clippy::missing_inline_in_public_items)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedReadWrite")
+            .field("read", &self.read_grip())
+            .field("write", &self.write_grip())
+            .finish()
+    }
+}
+
+impl OwnedReadable {
+    /// Creates a new `OwnedReadable` for the same underlying handle.
+    ///
+    /// The new handle is a duplicate, created with close-on-exec set on
+    /// Posix-ish platforms, and not inheritable on Windows. See
+    /// [`grip::try_clone_to_owned`] for details.
+    ///
+    /// [`grip::try_clone_to_owned`]: crate::grip::try_clone_to_owned
+    #[inline]
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self::from_grip(crate::grip::try_clone_to_owned(self)?))
+    }
+
+    /// Receives data from a socket without removing it from the input queue.
+    ///
+    /// See [`RawReadable::peek`] for details.
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.peek(buf)
+    }
+
+    /// Receives data from a socket, passing `flags` to the underlying `recv`
+    /// call.
+    ///
+    /// See [`RawReadable::recv_with_flags`] for details.
+    #[cfg(unix)]
+    #[inline]
+    pub fn recv_with_flags(&self, buf: &mut [u8], flags: RecvFlags) -> io::Result<(usize, usize)> {
+        self.0.recv_with_flags(buf, flags)
+    }
+
+    /// Like [`Read::read`], but reads into a possibly-uninitialized buffer.
+    ///
+    /// See [`RawReadable::read_uninit`] for details.
+    #[inline]
+    pub fn read_uninit(&mut self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+        self.0.read_uninit(buf)
+    }
+
+    /// Reads into the spare capacity of `buf`, and extends its length by the
+    /// number of bytes read.
+    ///
+    /// See [`RawReadable::read_buf`] for details.
+    #[cfg(feature = "bytes")]
+    #[inline]
+    pub fn read_buf(&mut self, buf: &mut BytesMut) -> io::Result<usize> {
+        self.0.read_buf(buf)
+    }
+
+    /// Returns the number of bytes that can currently be read.
+    ///
+    /// See [`grip::bytes_available`] for details.
+    ///
+    /// [`grip::bytes_available`]: crate::grip::bytes_available
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn bytes_available(&self) -> io::Result<u64> {
+        crate::grip::bytes_available(self)
+    }
+
+    /// Tests whether the other end has hung up, without consuming any data.
+    ///
+    /// See [`grip::is_peer_closed`] for details.
+    ///
+    /// [`grip::is_peer_closed`]: crate::grip::is_peer_closed
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn is_peer_closed(&self) -> io::Result<bool> {
+        crate::grip::is_peer_closed(self)
+    }
+
+    /// Shuts down the read half of a socket.
+    ///
+    /// See [`grip::shutdown`] for details.
+    ///
+    /// [`grip::shutdown`]: crate::grip::shutdown
+    #[inline]
+    pub fn shutdown_read(&self) -> io::Result<()> {
+        crate::grip::shutdown(self, Shutdown::Read)
+    }
+
+    /// Tests whether reads have an efficient vectored implementation.
+    ///
+    /// See [`vectored::is_read_vectored`] for details.
+    ///
+    /// [`vectored::is_read_vectored`]: crate::vectored::is_read_vectored
+    #[inline]
+    pub fn is_read_vectored(&self) -> bool {
+        crate::vectored::is_read_vectored(self)
+    }
+
+    /// Closes the handle, reporting any error from the OS.
+    ///
+    /// See [`grip::close`] for details.
+    ///
+    /// [`grip::close`]: crate::grip::close
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn close(self) -> io::Result<()> {
+        crate::grip::close(self.into())
+    }
+
+    /// Closes the socket abortively, resetting the connection.
+    ///
+    /// See [`grip::close_abortive`] for details.
+    ///
+    /// [`grip::close_abortive`]: crate::grip::close_abortive
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn close_abortive(self) -> io::Result<()> {
+        crate::grip::close_abortive(self.into())
+    }
+}
+
+impl OwnedWriteable {
+    /// Creates a new `OwnedWriteable` for the same underlying handle.
+    ///
+    /// The new handle is a duplicate, created with close-on-exec set on
+    /// Posix-ish platforms, and not inheritable on Windows. See
+    /// [`grip::try_clone_to_owned`] for details.
+    ///
+    /// [`grip::try_clone_to_owned`]: crate::grip::try_clone_to_owned
+    #[inline]
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self::from_grip(crate::grip::try_clone_to_owned(self)?))
+    }
+
+    /// Tests whether the other end has hung up, without consuming any data.
+    ///
+    /// See [`grip::is_peer_closed`] for details.
+    ///
+    /// [`grip::is_peer_closed`]: crate::grip::is_peer_closed
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn is_peer_closed(&self) -> io::Result<bool> {
+        crate::grip::is_peer_closed(self)
+    }
+
+    /// Shuts down the write half of a socket.
+    ///
+    /// See [`grip::shutdown`] for details.
+    ///
+    /// [`grip::shutdown`]: crate::grip::shutdown
+    #[inline]
+    pub fn shutdown_write(&self) -> io::Result<()> {
+        crate::grip::shutdown(self, Shutdown::Write)
+    }
+
+    /// Tests whether writes have an efficient vectored implementation.
+    ///
+    /// See [`vectored::is_write_vectored`] for details.
+    ///
+    /// [`vectored::is_write_vectored`]: crate::vectored::is_write_vectored
+    #[inline]
+    pub fn is_write_vectored(&self) -> bool {
+        crate::vectored::is_write_vectored(self)
+    }
+
+    /// Returns a writer which writes to this handle without raising
+    /// `SIGPIPE`.
+    ///
+    /// See [`NoSigpipe`] for details.
+    #[inline]
+    pub fn no_sigpipe(&self) -> NoSigpipe<BorrowedWriteable<'_>> {
+        NoSigpipe::new(BorrowedWriteable::borrow(self.as_grip()))
+    }
+
+    /// Synchronizes the file's content and metadata to storage.
+    ///
+    /// See [`RawWriteable::sync_all`] for details.
+    #[inline]
+    pub fn sync_all(&self) -> io::Result<()> {
+        self.0.sync_all()
+    }
+
+    /// Synchronizes the file's content to storage.
+    ///
+    /// See [`RawWriteable::sync_data`] for details.
+    #[inline]
+    pub fn sync_data(&self) -> io::Result<()> {
+        self.0.sync_data()
+    }
+
+    /// Closes the handle, reporting any error from the OS.
+    ///
+    /// See [`grip::close`] for details.
+    ///
+    /// [`grip::close`]: crate::grip::close
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn close(self) -> io::Result<()> {
+        crate::grip::close(self.into())
+    }
+
+    /// Closes the socket abortively, resetting the connection.
+    ///
+    /// See [`grip::close_abortive`] for details.
+    ///
+    /// [`grip::close_abortive`]: crate::grip::close_abortive
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn close_abortive(self) -> io::Result<()> {
+        crate::grip::close_abortive(self.into())
+    }
+}
+
 /// `OwnedReadable` owns its handle.
 #[cfg(not(windows))]
 impl AsFd for OwnedReadable {
@@ -60,6 +494,8 @@ impl AsFd for OwnedReadable {
 impl From<OwnedReadable> for OwnedFd {
     #[inline]
     fn from(owned: OwnedReadable) -> Self {
+        // Don't run `OwnedReadable`'s `Drop`, which would close the fd.
+        let owned = ManuallyDrop::new(owned);
         unsafe { Self::from_raw_fd(owned.0.into_raw_fd()) }
     }
 }
@@ -87,7 +523,9 @@ impl AsFd for OwnedWriteable {
 impl From<OwnedWriteable> for OwnedFd {
     #[inline]
     fn from(owned: OwnedWriteable) -> Self {
-        unsafe { Self::from_raw_fd(owned.0.as_raw_fd()) }
+        // Don't run `OwnedWriteable`'s `Drop`, which would close the fd.
+        let owned = ManuallyDrop::new(owned);
+        unsafe { Self::from_raw_fd(owned.0.into_raw_fd()) }
     }
 }
 
@@ -116,6 +554,8 @@ impl AsHandleOrSocket for OwnedReadable {
 impl From<OwnedReadable> for OwnedHandleOrSocket {
     #[inline]
     fn from(readable: OwnedReadable) -> Self {
+        // Don't run `OwnedReadable`'s `Drop`, which would close the handle.
+        let readable = ManuallyDrop::new(readable);
         unsafe {
             OwnedHandleOrSocket::from_raw_handle_or_socket(readable.0.into_raw_handle_or_socket())
         }
@@ -158,6 +598,8 @@ impl AsHandleOrSocket for OwnedWriteable {
 impl From<OwnedWriteable> for OwnedHandleOrSocket {
     #[inline]
     fn from(writeable: OwnedWriteable) -> Self {
+        // Don't run `OwnedWriteable`'s `Drop`, which would close the handle.
+        let writeable = ManuallyDrop::new(writeable);
         unsafe {
             OwnedHandleOrSocket::from_raw_handle_or_socket(writeable.0.into_raw_handle_or_socket())
         }
@@ -218,6 +660,12 @@ impl Read for OwnedReadable {
     fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
         self.0.read_exact(buf)
     }
+
+    #[cfg(read_buf)]
+    #[inline]
+    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> io::Result<()> {
+        Read::read_buf(&mut self.0, cursor)
+    }
 }
 
 #[cfg(windows)]
@@ -252,6 +700,12 @@ impl Read for OwnedReadable {
     fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
         self.0.read_exact(buf)
     }
+
+    #[cfg(read_buf)]
+    #[inline]
+    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> io::Result<()> {
+        Read::read_buf(&mut self.0, cursor)
+    }
 }
 
 #[cfg(not(windows))]
@@ -334,6 +788,88 @@ impl Write for OwnedWriteable {
     }
 }
 
+/// `RawReadable` is `Copy` and doesn't own its handle, so a copy of it can
+/// read on behalf of a shared reference, as with `&File`.
+impl Read for &OwnedReadable {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        { self.0 }.read(buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        { self.0 }.read_vectored(bufs)
+    }
+
+    #[cfg(can_vector)]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        self.0.is_read_vectored()
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        { self.0 }.read_to_end(buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        { self.0 }.read_to_string(buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        { self.0 }.read_exact(buf)
+    }
+
+    #[cfg(read_buf)]
+    #[inline]
+    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> io::Result<()> {
+        Read::read_buf(&mut { self.0 }, cursor)
+    }
+}
+
+/// `RawWriteable` is `Copy` and doesn't own its handle, so a copy of it can
+/// write on behalf of a shared reference, as with `&File`.
+impl Write for &OwnedWriteable {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        { self.0 }.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        { self.0 }.flush()
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        { self.0 }.write_vectored(bufs)
+    }
+
+    #[cfg(can_vector)]
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        self.0.is_write_vectored()
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        { self.0 }.write_all(buf)
+    }
+
+    #[cfg(write_all_vectored)]
+    #[inline]
+    fn write_all_vectored(&mut self, bufs: &mut [IoSlice<'_>]) -> io::Result<()> {
+        { self.0 }.write_all_vectored(bufs)
+    }
+
+    #[inline]
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> io::Result<()> {
+        { self.0 }.write_fmt(fmt)
+    }
+}
+
 impl Drop for OwnedReadable {
     #[inline]
     fn drop(&mut self) {
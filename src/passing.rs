@@ -0,0 +1,357 @@
+//! Sending and receiving grips over Unix-domain sockets.
+//!
+//! [`send_grips`] attaches grips to a message as `SCM_RIGHTS` ancillary
+//! data, and [`recv_grips`] receives them, as new grips owned by the
+//! receiver. These work with any Unix-domain socket, such as [`UnixStream`]
+//! and [`UnixDatagram`]. The `tokio` and `async_std` submodules provide
+//! versions for those crates' sockets, under their respective features.
+//!
+//! [`UnixStream`]: std::os::unix::net::UnixStream
+//! [`UnixDatagram`]: std::os::unix::net::UnixDatagram
+
+use crate::credentials::PeerCredentials;
+use crate::grip::{AsGrip, BorrowedGrip, OwnedGrip};
+use ::rustix::net::{
+    recvmsg, sendmsg, RecvAncillaryBuffer, RecvAncillaryMessage, RecvFlags, ReturnFlags,
+    SendAncillaryBuffer, SendAncillaryMessage, SendFlags,
+};
+use cloexec::{recv_flags, set_cloexec};
+use std::io::{self, IoSlice, IoSliceMut};
+use std::mem::MaybeUninit;
+
+/// Sends `data` over the Unix-domain socket `socket`, with `grips` attached.
+///
+/// The receiver gets duplicates of `grips`; they remain open in this
+/// process. The grips are attached to the first byte sent, so on a stream
+/// socket, `data` should be non-empty. Returns the number of bytes sent, which
+/// on a stream socket may be less than `data.len()`; the grips are sent
+/// either way, so the rest should be sent with plain writes.
+///
+/// On platforms which support it, this doesn't raise `SIGPIPE` if the peer
+/// has closed its end; it fails with [`io::ErrorKind::BrokenPipe`] instead.
+pub fn send_grips<Socket: AsGrip>(
+    socket: &Socket,
+    data: &[u8],
+    grips: &[BorrowedGrip<'_>],
+) -> io::Result<usize> {
+    let mut space = vec![MaybeUninit::uninit(); ::rustix::cmsg_space!(ScmRights(grips.len()))];
+    let mut control = SendAncillaryBuffer::new(&mut space);
+    if !grips.is_empty() {
+        let pushed = control.push(SendAncillaryMessage::ScmRights(grips));
+        debug_assert!(pushed, "the control buffer is sized for the grips");
+    }
+
+    Ok(sendmsg(
+        socket.as_grip(),
+        &[IoSlice::new(data)],
+        &mut control,
+        send_flags(),
+    )?)
+}
+
+/// Receives data into `buf` from the Unix-domain socket `socket`, along with
+/// up to `max_grips` grips attached to it.
+///
+/// Returns the number of bytes received and the grips, which have
+/// close-on-exec set.
+///
+/// If the sender attached more than `max_grips` grips, the extra grips are
+/// closed, and this fails with [`io::ErrorKind::InvalidData`], since grips
+/// have been lost. Similarly, on a datagram or seqpacket socket, this fails
+/// if the message was larger than `buf`. In both cases, the message has been
+/// consumed.
+pub fn recv_grips<Socket: AsGrip>(
+    socket: &Socket,
+    buf: &mut [u8],
+    max_grips: usize,
+) -> io::Result<(usize, Vec<OwnedGrip>)> {
+    let mut space = vec![MaybeUninit::uninit(); ::rustix::cmsg_space!(ScmRights(max_grips))];
+    let (bytes, grips, _) = recv(socket, buf, max_grips, &mut space)?;
+    Ok((bytes, grips))
+}
+
+/// Like [`recv_grips`], but also returns the sender's credentials, as
+/// attached by the kernel with `SCM_CREDENTIALS`.
+///
+/// The kernel only attaches credentials if `SO_PASSCRED` is enabled on
+/// `socket`, with [`set_pass_credentials`]; otherwise this returns `None`
+/// for them. Unlike [`peer_credentials`], these reflect the sender at the
+/// time the message was sent, which matters for datagram sockets and for
+/// sockets which have been passed between processes.
+///
+/// [`set_pass_credentials`]: crate::credentials::set_pass_credentials
+/// [`peer_credentials`]: crate::credentials::peer_credentials
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn recv_grips_with_credentials<Socket: AsGrip>(
+    socket: &Socket,
+    buf: &mut [u8],
+    max_grips: usize,
+) -> io::Result<(usize, Vec<OwnedGrip>, Option<PeerCredentials>)> {
+    let mut space =
+        vec![MaybeUninit::uninit(); ::rustix::cmsg_space!(ScmRights(max_grips), ScmCredentials(1))];
+    recv(socket, buf, max_grips, &mut space)
+}
+
+fn recv<Socket: AsGrip>(
+    socket: &Socket,
+    buf: &mut [u8],
+    max_grips: usize,
+    space: &mut [MaybeUninit<u8>],
+) -> io::Result<(usize, Vec<OwnedGrip>, Option<PeerCredentials>)> {
+    let mut control = RecvAncillaryBuffer::new(space);
+
+    let msg = recvmsg(
+        socket.as_grip(),
+        &mut [IoSliceMut::new(buf)],
+        &mut control,
+        recv_flags(),
+    )?;
+
+    // Collect the grips before checking for errors, so that we close them if
+    // we fail. The buffer may have room for more than `max_grips` due to
+    // alignment padding, so count anything beyond that as truncation too.
+    let mut grips = Vec::new();
+    #[cfg_attr(
+        not(any(target_os = "android", target_os = "linux")),
+        allow(unused_mut)
+    )]
+    let mut credentials = None;
+    for message in control.drain() {
+        match message {
+            RecvAncillaryMessage::ScmRights(fds) => grips.extend(fds),
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            RecvAncillaryMessage::ScmCredentials(ucred) => {
+                credentials = Some(PeerCredentials::from_ucred(ucred))
+            }
+            _ => {}
+        }
+    }
+    let surplus = grips.len() > max_grips;
+    grips.truncate(max_grips);
+
+    if surplus || msg.flags.contains(ReturnFlags::CTRUNC) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "received more grips than the maximum of {}; extra grips were closed",
+                max_grips
+            ),
+        ));
+    }
+    if msg.flags.contains(ReturnFlags::TRUNC) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "received message was larger than the buffer",
+        ));
+    }
+
+    set_cloexec(&grips)?;
+    Ok((msg.bytes, grips, credentials))
+}
+
+#[cfg(not(any(target_vendor = "apple", target_os = "redox", target_os = "vita")))]
+#[inline]
+pub(crate) fn send_flags() -> SendFlags {
+    SendFlags::NOSIGNAL
+}
+
+#[cfg(any(target_vendor = "apple", target_os = "redox", target_os = "vita"))]
+#[inline]
+pub(crate) fn send_flags() -> SendFlags {
+    SendFlags::empty()
+}
+
+#[cfg(not(any(
+    target_vendor = "apple",
+    target_os = "solaris",
+    target_os = "illumos",
+    target_os = "aix",
+    target_os = "haiku",
+    target_os = "nto",
+)))]
+mod cloexec {
+    use super::{OwnedGrip, RecvFlags};
+    use std::io;
+
+    #[inline]
+    pub(super) fn recv_flags() -> RecvFlags {
+        RecvFlags::CMSG_CLOEXEC
+    }
+
+    #[inline]
+    pub(super) fn set_cloexec(_grips: &[OwnedGrip]) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Without `MSG_CMSG_CLOEXEC`, set close-on-exec after the fact. A
+/// concurrent `fork` and `exec` may still leak the grips into a child.
+#[cfg(any(
+    target_vendor = "apple",
+    target_os = "solaris",
+    target_os = "illumos",
+    target_os = "aix",
+    target_os = "haiku",
+    target_os = "nto",
+))]
+mod cloexec {
+    use super::{OwnedGrip, RecvFlags};
+    use ::rustix::io::{fcntl_setfd, FdFlags};
+    use std::io;
+
+    #[inline]
+    pub(super) fn recv_flags() -> RecvFlags {
+        RecvFlags::empty()
+    }
+
+    pub(super) fn set_cloexec(grips: &[OwnedGrip]) -> io::Result<()> {
+        for grip in grips {
+            fcntl_setfd(grip, FdFlags::CLOEXEC)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sending and receiving grips over tokio's Unix-domain sockets.
+#[cfg(feature = "tokio")]
+pub mod tokio {
+    use crate::grip::{BorrowedGrip, OwnedGrip};
+    use ::tokio::io::Interest;
+    use ::tokio::net::{UnixDatagram, UnixStream};
+    use std::io;
+
+    /// Like [`send_grips`](super::send_grips), but waits for `socket` to be
+    /// writable.
+    pub async fn send_grips(
+        socket: &UnixStream,
+        data: &[u8],
+        grips: &[BorrowedGrip<'_>],
+    ) -> io::Result<usize> {
+        socket
+            .async_io(Interest::WRITABLE, || {
+                super::send_grips(socket, data, grips)
+            })
+            .await
+    }
+
+    /// Like [`recv_grips`](super::recv_grips), but waits for `socket` to be
+    /// readable.
+    pub async fn recv_grips(
+        socket: &UnixStream,
+        buf: &mut [u8],
+        max_grips: usize,
+    ) -> io::Result<(usize, Vec<OwnedGrip>)> {
+        socket
+            .async_io(Interest::READABLE, || {
+                super::recv_grips(socket, buf, max_grips)
+            })
+            .await
+    }
+
+    /// Like [`send_grips`](super::send_grips), for datagram sockets.
+    pub async fn send_grips_datagram(
+        socket: &UnixDatagram,
+        data: &[u8],
+        grips: &[BorrowedGrip<'_>],
+    ) -> io::Result<usize> {
+        socket
+            .async_io(Interest::WRITABLE, || {
+                super::send_grips(socket, data, grips)
+            })
+            .await
+    }
+
+    /// Like [`recv_grips`](super::recv_grips), for datagram sockets.
+    pub async fn recv_grips_datagram(
+        socket: &UnixDatagram,
+        buf: &mut [u8],
+        max_grips: usize,
+    ) -> io::Result<(usize, Vec<OwnedGrip>)> {
+        socket
+            .async_io(Interest::READABLE, || {
+                super::recv_grips(socket, buf, max_grips)
+            })
+            .await
+    }
+}
+
+/// Sending and receiving grips over async-std's Unix-domain sockets.
+///
+/// async-std doesn't expose its sockets' readiness, so these register a
+/// duplicate of the socket with async-std's reactor for the duration of the
+/// call.
+#[cfg(all(feature = "async-std", feature = "async-io"))]
+pub mod async_std {
+    use crate::grip::{AsGrip, BorrowedGrip, OwnedGrip};
+    use async_io::Async;
+    use async_std::os::unix::net::{UnixDatagram, UnixStream};
+    use std::io;
+
+    /// Like [`send_grips`](super::send_grips), but waits for `socket` to be
+    /// writable.
+    pub async fn send_grips(
+        socket: &UnixStream,
+        data: &[u8],
+        grips: &[BorrowedGrip<'_>],
+    ) -> io::Result<usize> {
+        let waiter = waiter(socket)?;
+        loop {
+            match super::send_grips(socket, data, grips) {
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => waiter.writable().await?,
+                result => return result,
+            }
+        }
+    }
+
+    /// Like [`recv_grips`](super::recv_grips), but waits for `socket` to be
+    /// readable.
+    pub async fn recv_grips(
+        socket: &UnixStream,
+        buf: &mut [u8],
+        max_grips: usize,
+    ) -> io::Result<(usize, Vec<OwnedGrip>)> {
+        let waiter = waiter(socket)?;
+        loop {
+            match super::recv_grips(socket, buf, max_grips) {
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => waiter.readable().await?,
+                result => return result,
+            }
+        }
+    }
+
+    /// Like [`send_grips`](super::send_grips), for datagram sockets.
+    pub async fn send_grips_datagram(
+        socket: &UnixDatagram,
+        data: &[u8],
+        grips: &[BorrowedGrip<'_>],
+    ) -> io::Result<usize> {
+        let waiter = waiter(socket)?;
+        loop {
+            match super::send_grips(socket, data, grips) {
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => waiter.writable().await?,
+                result => return result,
+            }
+        }
+    }
+
+    /// Like [`recv_grips`](super::recv_grips), for datagram sockets.
+    pub async fn recv_grips_datagram(
+        socket: &UnixDatagram,
+        buf: &mut [u8],
+        max_grips: usize,
+    ) -> io::Result<(usize, Vec<OwnedGrip>)> {
+        let waiter = waiter(socket)?;
+        loop {
+            match super::recv_grips(socket, buf, max_grips) {
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => waiter.readable().await?,
+                result => return result,
+            }
+        }
+    }
+
+    /// Registers a duplicate of `socket` with the reactor, since the socket
+    /// itself is already registered by async-std.
+    fn waiter<Socket: AsGrip>(socket: &Socket) -> io::Result<Async<OwnedGrip>> {
+        Async::new_nonblocking(crate::grip::try_clone_to_owned(socket)?)
+    }
+}
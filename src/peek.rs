@@ -0,0 +1,121 @@
+//! `Peekable`, which adds a `peek` operation to any readable grip.
+
+use crate::borrowed::BorrowedReadable;
+use crate::grip::{AsGrip, BorrowedGrip};
+use std::cmp::min;
+use std::io::{self, Read};
+
+/// Adapts a readable grip to support [`peek`] on any kind of handle.
+///
+/// If the handle is a socket, `peek` asks the OS to receive data without
+/// removing it from the input queue. Otherwise, such as for pipes, peeked
+/// data is read into an internal buffer, and subsequent reads return it
+/// before reading any new data.
+///
+/// [`peek`]: Self::peek
+#[derive(Debug)]
+pub struct Peekable<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+    is_socket: bool,
+}
+
+impl<R: AsGrip + Read> Peekable<R> {
+    /// Wraps `inner` to support `peek`.
+    #[inline]
+    pub fn new(inner: R) -> Self {
+        let is_socket = is_socket(inner.as_grip());
+        Self {
+            inner,
+            buf: Vec::new(),
+            pos: 0,
+            is_socket,
+        }
+    }
+
+    /// Reads data into `buf` without consuming it, so that it's returned
+    /// again by the next `peek` or `read`.
+    ///
+    /// If the handle isn't a socket and fewer than `buf.len()` bytes are
+    /// buffered, this performs one read to try to fill the rest, so, like
+    /// `read`, it may block.
+    pub fn peek(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        #[cfg(any(unix, windows))]
+        if self.is_socket {
+            return BorrowedReadable::borrow(self.inner.as_grip()).peek(buf);
+        }
+
+        if self.buf.len() - self.pos < buf.len() {
+            self.buf.drain(..self.pos);
+            self.pos = 0;
+
+            let old_len = self.buf.len();
+            self.buf.resize(buf.len(), 0);
+            match self.inner.read(&mut self.buf[old_len..]) {
+                Ok(n) => self.buf.truncate(old_len + n),
+                Err(err) => {
+                    self.buf.truncate(old_len);
+                    if old_len == 0 {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+
+        let n = min(buf.len(), self.buf.len() - self.pos);
+        buf[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        Ok(n)
+    }
+
+    /// Returns a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Consumes this `Peekable`, returning the underlying reader and any
+    /// peeked data which hasn't yet been read.
+    #[inline]
+    pub fn into_parts(mut self) -> (R, Vec<u8>) {
+        self.buf.drain(..self.pos);
+        (self.inner, self.buf)
+    }
+}
+
+impl<R: AsGrip + Read> Read for Peekable<R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.buf.len() {
+            return self.inner.read(buf);
+        }
+
+        let n = min(buf.len(), self.buf.len() - self.pos);
+        buf[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        if self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(unix)]
+fn is_socket(grip: BorrowedGrip<'_>) -> bool {
+    use ::rustix::fs::{fstat, FileType};
+
+    fstat(grip)
+        .map(|stat| FileType::from_raw_mode(stat.st_mode) == FileType::Socket)
+        .unwrap_or(false)
+}
+
+#[cfg(windows)]
+fn is_socket(grip: BorrowedGrip<'_>) -> bool {
+    grip.as_socket().is_some()
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_socket(_grip: BorrowedGrip<'_>) -> bool {
+    false
+}
@@ -0,0 +1,232 @@
+//! Pseudoterminals, for running programs as if on a terminal.
+//!
+//! [`open`] creates a pseudoterminal, returning its master side, which a
+//! test or terminal emulator reads from and writes to, and its slave side,
+//! which a child process uses as its terminal. [`CommandPtyExt::pty`] sets
+//! up a child process to use the slave side as its stdin, stdout, stderr,
+//! and controlling terminal.
+
+use crate::os::rustix::{AsReadWriteFd, RawFd};
+use ::rustix::fs::{open as open_path, Mode, OFlags};
+use ::rustix::io::Errno;
+use ::rustix::pty::{grantpt, openpt, ptsname, unlockpt, OpenptFlags};
+use ::rustix::termios::{tcgetwinsize, tcsetwinsize, Winsize};
+use io_lifetimes::{AsFd, BorrowedFd, OwnedFd};
+use std::io::{self, Read, Write};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+
+/// The size of a terminal window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(clippy::exhaustive_structs)]
+pub struct WindowSize {
+    /// The number of rows of characters.
+    pub rows: u16,
+
+    /// The number of columns of characters.
+    pub cols: u16,
+}
+
+/// The master side of a pseudoterminal.
+///
+/// Data written to this is input to the terminal, and data read from this is
+/// the terminal's output. Once the slave side has been closed, including by
+/// all child processes using it, reads return end-of-file.
+#[derive(Debug)]
+pub struct PtyMaster(OwnedFd);
+
+/// The slave side of a pseudoterminal, which a child process uses as its
+/// terminal.
+#[derive(Debug)]
+pub struct PtySlave(OwnedFd);
+
+/// Opens a new pseudoterminal, returning its master and slave sides, with
+/// close-on-exec set.
+///
+/// Neither side becomes the controlling terminal of this process.
+pub fn open() -> io::Result<(PtyMaster, PtySlave)> {
+    let master = open_master()?;
+    grantpt(&master)?;
+    unlockpt(&master)?;
+    let name = ptsname(&master, Vec::new())?;
+    let slave = open_path(
+        name.as_c_str(),
+        OFlags::RDWR | OFlags::NOCTTY | OFlags::CLOEXEC,
+        Mode::empty(),
+    )?;
+    Ok((PtyMaster(master), PtySlave(slave)))
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "netbsd"
+))]
+fn open_master() -> io::Result<OwnedFd> {
+    Ok(openpt(
+        OpenptFlags::RDWR | OpenptFlags::NOCTTY | OpenptFlags::CLOEXEC,
+    )?)
+}
+
+/// Without `O_CLOEXEC` support in `posix_openpt`, set close-on-exec after
+/// the fact. A concurrent `fork` and `exec` may leak the master into a child.
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "netbsd"
+)))]
+fn open_master() -> io::Result<OwnedFd> {
+    use ::rustix::io::{fcntl_setfd, FdFlags};
+
+    let master = openpt(OpenptFlags::RDWR | OpenptFlags::NOCTTY)?;
+    fcntl_setfd(&master, FdFlags::CLOEXEC)?;
+    Ok(master)
+}
+
+impl PtyMaster {
+    /// Returns the terminal's window size.
+    #[inline]
+    pub fn window_size(&self) -> io::Result<WindowSize> {
+        window_size(&self.0)
+    }
+
+    /// Sets the terminal's window size, which sends `SIGWINCH` to the
+    /// terminal's foreground process group.
+    #[inline]
+    pub fn set_window_size(&self, size: WindowSize) -> io::Result<()> {
+        Ok(tcsetwinsize(
+            &self.0,
+            Winsize {
+                ws_row: size.rows,
+                ws_col: size.cols,
+                ws_xpixel: 0,
+                ws_ypixel: 0,
+            },
+        )?)
+    }
+}
+
+impl PtySlave {
+    /// Returns a duplicate of this grip, for use as a child's stdio.
+    #[inline]
+    pub fn stdio(&self) -> io::Result<Stdio> {
+        Ok(Stdio::from(crate::grip::try_clone_to_owned(&self.0)?))
+    }
+}
+
+pub(crate) fn window_size(fd: &impl AsFd) -> io::Result<WindowSize> {
+    let winsize = tcgetwinsize(fd)?;
+    Ok(WindowSize {
+        rows: winsize.ws_row,
+        cols: winsize.ws_col,
+    })
+}
+
+impl Read for PtyMaster {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match ::rustix::io::read(&self.0, buf) {
+            Ok(n) => Ok(n),
+            // Linux reports `EIO` once the slave side has been closed.
+            Err(Errno::IO) => Ok(0),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+impl Write for PtyMaster {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(::rustix::io::write(&self.0, buf)?)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl AsFd for PtyMaster {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+impl AsReadWriteFd for PtyMaster {
+    #[inline]
+    fn as_read_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+
+    #[inline]
+    fn as_write_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+impl From<PtyMaster> for OwnedFd {
+    #[inline]
+    fn from(master: PtyMaster) -> Self {
+        master.0
+    }
+}
+
+impl AsFd for PtySlave {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+impl From<PtySlave> for OwnedFd {
+    #[inline]
+    fn from(slave: PtySlave) -> Self {
+        slave.0
+    }
+}
+
+/// Extension trait for process builders, for running the child on a
+/// pseudoterminal.
+pub trait CommandPtyExt {
+    /// Sets the child's stdin, stdout, and stderr to `slave`, and makes it
+    /// the child's controlling terminal, in a new session.
+    ///
+    /// This fails if duplicating `slave` fails.
+    fn pty(&mut self, slave: &PtySlave) -> io::Result<&mut Self>;
+}
+
+impl CommandPtyExt for Command {
+    #[inline]
+    fn pty(&mut self, slave: &PtySlave) -> io::Result<&mut Self> {
+        self.stdin(slave.stdio()?)
+            .stdout(slave.stdio()?)
+            .stderr(slave.stdio()?);
+        // Safety: `set_controlling_terminal` is async-signal-safe.
+        Ok(unsafe { self.pre_exec(set_controlling_terminal) })
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl CommandPtyExt for tokio::process::Command {
+    #[inline]
+    fn pty(&mut self, slave: &PtySlave) -> io::Result<&mut Self> {
+        self.stdin(slave.stdio()?)
+            .stdout(slave.stdio()?)
+            .stderr(slave.stdio()?);
+        // Safety: `set_controlling_terminal` is async-signal-safe.
+        Ok(unsafe { self.pre_exec(set_controlling_terminal) })
+    }
+}
+
+/// Runs in the child between `fork` and `exec`, after stdio is set up.
+fn set_controlling_terminal() -> io::Result<()> {
+    const STDIN: RawFd = 0;
+
+    ::rustix::process::setsid()?;
+    // Safety: stdin is open, and is the pseudoterminal's slave side.
+    let stdin = unsafe { BorrowedFd::borrow_raw(STDIN) };
+    Ok(::rustix::process::ioctl_tiocsctty(stdin)?)
+}
@@ -1,13 +1,20 @@
 //! `RawReadable` and `RawWriteable`.
 
+use crate::grip::borrow_raw;
 use crate::grip::RawGrip;
 #[cfg(not(windows))]
 use crate::os::rustix::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+#[cfg(feature = "bytes")]
+use bytes::BytesMut;
 use io_lifetimes::raw::RawFilelike;
 use io_lifetimes::views::FilelikeView;
 use std::fmt;
 use std::fs::File;
+#[cfg(read_buf)]
+use std::io::BorrowedCursor;
 use std::io::{self, IoSlice, IoSliceMut, Read, Write};
+use std::mem::MaybeUninit;
+use std::net::Shutdown;
 #[cfg(all(doc, not(windows)))]
 use std::net::TcpStream;
 #[cfg(windows)]
@@ -21,6 +28,8 @@ use {
     std::net::TcpStream,
     std::os::windows::io::{FromRawHandle, RawHandle},
 };
+#[cfg(unix)]
+use {::rustix::net::RecvFlags, io_lifetimes::BorrowedFd};
 
 /// A non-owning unsafe I/O handle that implements [`Read`]. `Read` functions
 /// are considered safe, so this type requires `unsafe` to construct.
@@ -178,17 +187,177 @@ impl FromRawHandle for RawWriteable {
     }
 }
 
+/// The amount to grow a buffer by when reading into a full buffer.
+#[cfg(feature = "bytes")]
+const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+
 #[inline]
 unsafe fn as_file_view<'a>(file: RawFilelike) -> FilelikeView<'a, File> {
     FilelikeView::<'a>::view_raw(file)
 }
 
+#[cfg(windows)]
+fn not_a_file() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "handle is not a file")
+}
+
 #[cfg(windows)]
 #[inline]
 unsafe fn as_socket_view<'a>(socket: RawSocketlike) -> SocketlikeView<'a, TcpStream> {
     SocketlikeView::<'a>::view_raw(socket)
 }
 
+impl RawReadable {
+    /// Like [`Read::read`], but reads into a possibly-uninitialized buffer.
+    ///
+    /// On success, the first `n` bytes of `buf` are initialized, where `n`
+    /// is the returned value.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On Posix-ish platforms, this reads directly into `buf`. On other
+    /// platforms, `buf` is zeroed before reading.
+    #[cfg(unix)]
+    #[inline]
+    pub fn read_uninit(&mut self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+        // Safety: See the comments in `read`.
+        let fd = unsafe { BorrowedFd::borrow_raw(self.0) };
+        let (init, _uninit) = ::rustix::io::read(fd, buf)?;
+        Ok(init.len())
+    }
+
+    /// Like [`Read::read`], but reads into a possibly-uninitialized buffer.
+    ///
+    /// On success, the first `n` bytes of `buf` are initialized, where `n`
+    /// is the returned value.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On Posix-ish platforms, this reads directly into `buf`. On other
+    /// platforms, `buf` is zeroed before reading.
+    #[cfg(not(unix))]
+    #[inline]
+    pub fn read_uninit(&mut self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+        for byte in buf.iter_mut() {
+            byte.write(0);
+        }
+        // Safety: We just initialized every byte of `buf`.
+        let buf = unsafe { &mut *(buf as *mut [MaybeUninit<u8>] as *mut [u8]) };
+        self.read(buf)
+    }
+
+    /// Reads into the spare capacity of `buf`, and extends its length by the
+    /// number of bytes read.
+    ///
+    /// If `buf` has no spare capacity, it's grown first. To call
+    /// [`Read::read_buf`], where available, use `Read::read_buf(&mut
+    /// readable, cursor)`.
+    #[cfg(feature = "bytes")]
+    #[inline]
+    pub fn read_buf(&mut self, buf: &mut BytesMut) -> io::Result<usize> {
+        if buf.len() == buf.capacity() {
+            buf.reserve(DEFAULT_BUF_SIZE);
+        }
+        let n = self.read_uninit(buf.spare_capacity_mut())?;
+        // Safety: `read_uninit` initialized `n` bytes of the spare capacity.
+        unsafe { buf.set_len(buf.len() + n) };
+        Ok(n)
+    }
+
+    /// Receives data from a socket without removing it from the input queue.
+    ///
+    /// This fails if the handle isn't a socket. To peek at data from any kind
+    /// of handle, use [`Peekable`].
+    ///
+    /// [`Peekable`]: crate::peek::Peekable
+    #[cfg(unix)]
+    #[inline]
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.recv_with_flags(buf, RecvFlags::PEEK)
+            .map(|(len, _)| len)
+    }
+
+    /// Receives data from a socket, passing `flags` to the underlying `recv`
+    /// call.
+    ///
+    /// Returns the number of bytes written into `buf`, followed by the length
+    /// reported by the OS, which may be greater than the first if
+    /// [`RecvFlags::TRUNC`] is used and the message was truncated.
+    ///
+    /// This fails if the handle isn't a socket.
+    #[cfg(unix)]
+    #[inline]
+    pub fn recv_with_flags(&self, buf: &mut [u8], flags: RecvFlags) -> io::Result<(usize, usize)> {
+        // Safety: See the comments in `read`.
+        let fd = unsafe { BorrowedFd::borrow_raw(self.0) };
+        Ok(::rustix::net::recv(fd, buf, flags)?)
+    }
+
+    /// Receives data from a socket without removing it from the input queue.
+    ///
+    /// This fails if the handle isn't a socket. To peek at data from any kind
+    /// of handle, use [`Peekable`].
+    ///
+    /// [`Peekable`]: crate::peek::Peekable
+    #[cfg(windows)]
+    #[inline]
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.0 .0 {
+            RawEnum::Socket(raw_socket) => unsafe { &as_socket_view(raw_socket) }.peek(buf),
+            RawEnum::Handle(_) | RawEnum::Stdio(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "peek is only supported on sockets",
+            )),
+        }
+    }
+
+    /// Returns the number of bytes that can currently be read.
+    ///
+    /// See [`grip::bytes_available`] for details.
+    ///
+    /// [`grip::bytes_available`]: crate::grip::bytes_available
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn bytes_available(&self) -> io::Result<u64> {
+        // Safety: See the comments in `read`.
+        crate::grip::bytes_available(&unsafe { borrow_raw(self.0) })
+    }
+
+    /// Tests whether the other end has hung up, without consuming any data.
+    ///
+    /// See [`grip::is_peer_closed`] for details.
+    ///
+    /// [`grip::is_peer_closed`]: crate::grip::is_peer_closed
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn is_peer_closed(&self) -> io::Result<bool> {
+        // Safety: See the comments in `read`.
+        crate::grip::is_peer_closed(&unsafe { borrow_raw(self.0) })
+    }
+
+    /// Shuts down the read half of a socket.
+    ///
+    /// See [`grip::shutdown`] for details.
+    ///
+    /// [`grip::shutdown`]: crate::grip::shutdown
+    #[inline]
+    pub fn shutdown_read(&self) -> io::Result<()> {
+        // Safety: See the comments in `read`.
+        crate::grip::shutdown(&unsafe { borrow_raw(self.0) }, Shutdown::Read)
+    }
+
+    /// Tests whether reads have an efficient vectored implementation.
+    ///
+    /// See [`vectored::is_read_vectored`] for details.
+    ///
+    /// [`vectored::is_read_vectored`]: crate::vectored::is_read_vectored
+    #[inline]
+    pub fn is_read_vectored(&self) -> bool {
+        // Safety: See the comments in `read`.
+        crate::vectored::is_read_vectored(&unsafe { borrow_raw(self.0) })
+    }
+}
+
 #[cfg(not(windows))]
 impl Read for RawReadable {
     #[inline]
@@ -224,6 +393,12 @@ impl Read for RawReadable {
     fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
         unsafe { &*as_file_view(self.0) }.read_exact(buf)
     }
+
+    #[cfg(read_buf)]
+    #[inline]
+    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> io::Result<()> {
+        unsafe { &*as_file_view(self.0) }.read_buf(cursor)
+    }
 }
 
 #[cfg(windows)]
@@ -292,6 +467,98 @@ impl Read for RawReadable {
             RawEnum::Stdio(ref mut stdio) => stdio.read_exact(buf),
         }
     }
+
+    #[cfg(read_buf)]
+    #[inline]
+    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> io::Result<()> {
+        match self.0 .0 {
+            RawEnum::Handle(raw_handle) => unsafe { &*as_file_view(raw_handle) }.read_buf(cursor),
+            RawEnum::Socket(raw_socket) => unsafe { &*as_socket_view(raw_socket) }.read_buf(cursor),
+            RawEnum::Stdio(ref mut stdio) => stdio.read_buf(cursor),
+        }
+    }
+}
+
+impl RawWriteable {
+    /// Tests whether the other end has hung up, without consuming any data.
+    ///
+    /// See [`grip::is_peer_closed`] for details.
+    ///
+    /// [`grip::is_peer_closed`]: crate::grip::is_peer_closed
+    #[cfg(any(unix, windows))]
+    #[inline]
+    pub fn is_peer_closed(&self) -> io::Result<bool> {
+        // Safety: See the comments in `write`.
+        crate::grip::is_peer_closed(&unsafe { borrow_raw(self.0) })
+    }
+
+    /// Shuts down the write half of a socket.
+    ///
+    /// See [`grip::shutdown`] for details.
+    ///
+    /// [`grip::shutdown`]: crate::grip::shutdown
+    #[inline]
+    pub fn shutdown_write(&self) -> io::Result<()> {
+        // Safety: See the comments in `write`.
+        crate::grip::shutdown(&unsafe { borrow_raw(self.0) }, Shutdown::Write)
+    }
+
+    /// Tests whether writes have an efficient vectored implementation.
+    ///
+    /// See [`vectored::is_write_vectored`] for details.
+    ///
+    /// [`vectored::is_write_vectored`]: crate::vectored::is_write_vectored
+    #[inline]
+    pub fn is_write_vectored(&self) -> bool {
+        // Safety: See the comments in `write`.
+        crate::vectored::is_write_vectored(&unsafe { borrow_raw(self.0) })
+    }
+
+    /// Synchronizes the file's content and metadata to storage.
+    ///
+    /// This is like [`File::sync_all`], and fails if the handle isn't a file.
+    #[cfg(not(windows))]
+    #[inline]
+    pub fn sync_all(&self) -> io::Result<()> {
+        // Safety: See the comments in `write`.
+        unsafe { as_file_view(self.0) }.sync_all()
+    }
+
+    /// Synchronizes the file's content to storage.
+    ///
+    /// This is like [`File::sync_data`], and fails if the handle isn't a
+    /// file.
+    #[cfg(not(windows))]
+    #[inline]
+    pub fn sync_data(&self) -> io::Result<()> {
+        // Safety: See the comments in `write`.
+        unsafe { as_file_view(self.0) }.sync_data()
+    }
+
+    /// Synchronizes the file's content and metadata to storage.
+    ///
+    /// This is like [`File::sync_all`], and fails if the handle isn't a file.
+    #[cfg(windows)]
+    #[inline]
+    pub fn sync_all(&self) -> io::Result<()> {
+        match self.0 .0 {
+            RawEnum::Handle(raw_handle) => unsafe { as_file_view(raw_handle) }.sync_all(),
+            RawEnum::Socket(_) | RawEnum::Stdio(_) => Err(not_a_file()),
+        }
+    }
+
+    /// Synchronizes the file's content to storage.
+    ///
+    /// This is like [`File::sync_data`], and fails if the handle isn't a
+    /// file.
+    #[cfg(windows)]
+    #[inline]
+    pub fn sync_data(&self) -> io::Result<()> {
+        match self.0 .0 {
+            RawEnum::Handle(raw_handle) => unsafe { as_file_view(raw_handle) }.sync_data(),
+            RawEnum::Socket(_) | RawEnum::Stdio(_) => Err(not_a_file()),
+        }
+    }
 }
 
 #[cfg(not(windows))]
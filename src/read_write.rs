@@ -1,10 +1,13 @@
 //! Traits for working with types that may have up to two I/O objects.
 
+use crate::grip::{AsReadWriteGrip, FromGrip};
 #[cfg(windows)]
 use crate::os::windows::{
     AsHandleOrSocket, AsRawHandleOrSocket, BorrowedHandleOrSocket, RawHandleOrSocket,
 };
+use crate::owned::{OwnedReadable, OwnedWriteable};
 use std::fs::File;
+use std::io;
 use std::net::TcpStream;
 #[cfg(unix)]
 use std::os::unix::net::UnixStream;
@@ -740,6 +743,19 @@ impl<'a, RW> ReadHalf<'a, RW> {
     }
 }
 
+impl<'a, RW: AsReadWriteGrip> ReadHalf<'a, RW> {
+    /// Creates a new `OwnedReadable` for the same underlying handle.
+    ///
+    /// See [`grip::try_clone_to_owned`] for details.
+    ///
+    /// [`grip::try_clone_to_owned`]: crate::grip::try_clone_to_owned
+    #[inline]
+    pub fn try_clone_to_owned(&self) -> io::Result<OwnedReadable> {
+        let grip = crate::grip::try_clone_to_owned(&self.0.as_read_grip())?;
+        Ok(OwnedReadable::from_grip(grip))
+    }
+}
+
 #[cfg(not(windows))]
 impl<'a, RW: AsReadWriteFd> AsFd for ReadHalf<'a, RW> {
     #[inline]
@@ -770,6 +786,19 @@ impl<'a, RW> WriteHalf<'a, RW> {
     }
 }
 
+impl<'a, RW: AsReadWriteGrip> WriteHalf<'a, RW> {
+    /// Creates a new `OwnedWriteable` for the same underlying handle.
+    ///
+    /// See [`grip::try_clone_to_owned`] for details.
+    ///
+    /// [`grip::try_clone_to_owned`]: crate::grip::try_clone_to_owned
+    #[inline]
+    pub fn try_clone_to_owned(&self) -> io::Result<OwnedWriteable> {
+        let grip = crate::grip::try_clone_to_owned(&self.0.as_write_grip())?;
+        Ok(OwnedWriteable::from_grip(grip))
+    }
+}
+
 #[cfg(not(windows))]
 impl<'a, RW: AsReadWriteFd> AsFd for WriteHalf<'a, RW> {
     #[inline]
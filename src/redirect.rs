@@ -0,0 +1,129 @@
+//! Scoped redirection of the process' stdin, stdout, and stderr.
+//!
+//! These point file descriptors 0, 1, or 2 at another grip, which affects
+//! everything in the process that uses them directly, such as C libraries
+//! which print to fd 1, and restore the original when the guard is dropped.
+
+use crate::grip::AsGrip;
+use ::rustix::stdio;
+use io_lifetimes::{AsFd, BorrowedFd, OwnedFd};
+use std::io::{self, Write};
+use std::ptr::null_mut;
+
+/// A guard which restores a stdio file descriptor when dropped.
+///
+/// Returned by [`redirect_stdin`], [`redirect_stdout`], and
+/// [`redirect_stderr`].
+#[derive(Debug)]
+#[must_use = "the redirection is undone when the guard is dropped"]
+pub struct Redirect {
+    stream: Stream,
+    saved: Option<OwnedFd>,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Stream {
+    Stdin,
+    Stdout,
+    Stderr,
+}
+
+/// Points fd 0 at `grip` until the returned guard is dropped.
+///
+/// Data that [`std::io::stdin`] has already buffered from the original
+/// stdin is still returned before data from `grip`.
+pub fn redirect_stdin<Grip: AsGrip>(grip: &Grip) -> io::Result<Redirect> {
+    redirect(Stream::Stdin, grip.as_grip())
+}
+
+/// Points fd 1 at `grip` until the returned guard is dropped.
+///
+/// [`std::io::stdout`] and C's `stdio` buffers are flushed before
+/// redirecting and before restoring, so that output goes where it was
+/// written.
+pub fn redirect_stdout<Grip: AsGrip>(grip: &Grip) -> io::Result<Redirect> {
+    redirect(Stream::Stdout, grip.as_grip())
+}
+
+/// Points fd 2 at `grip` until the returned guard is dropped.
+///
+/// [`std::io::stderr`] and C's `stdio` buffers are flushed before
+/// redirecting and before restoring, so that output goes where it was
+/// written.
+pub fn redirect_stderr<Grip: AsGrip>(grip: &Grip) -> io::Result<Redirect> {
+    redirect(Stream::Stderr, grip.as_grip())
+}
+
+impl Redirect {
+    /// Restores the original file descriptor, reporting any error.
+    ///
+    /// Dropping the guard does the same thing, but ignores errors.
+    #[inline]
+    pub fn restore(mut self) -> io::Result<()> {
+        self.restore_saved()
+    }
+
+    fn restore_saved(&mut self) -> io::Result<()> {
+        match self.saved.take() {
+            Some(saved) => self.stream.replace(saved.as_fd()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Redirect {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = self.restore_saved();
+    }
+}
+
+fn redirect(stream: Stream, grip: BorrowedFd<'_>) -> io::Result<Redirect> {
+    // Save the original above the stdio range, so that it doesn't get
+    // mistaken for one of them.
+    let saved = ::rustix::io::fcntl_dupfd_cloexec(stream.fd(), 3)?;
+    stream.replace(grip)?;
+    Ok(Redirect {
+        stream,
+        saved: Some(saved),
+    })
+}
+
+impl Stream {
+    fn fd(self) -> BorrowedFd<'static> {
+        match self {
+            Self::Stdin => stdio::stdin(),
+            Self::Stdout => stdio::stdout(),
+            Self::Stderr => stdio::stderr(),
+        }
+    }
+
+    /// Flushes any buffered output, and points the stream's file descriptor
+    /// at `grip`.
+    ///
+    /// The file descriptor is replaced even if flushing fails, so that a
+    /// redirection is always undone.
+    fn replace(self, grip: BorrowedFd<'_>) -> io::Result<()> {
+        let flushed = match self {
+            Self::Stdin => Ok(()),
+            Self::Stdout => flush_all(io::stdout()),
+            Self::Stderr => flush_all(io::stderr()),
+        };
+        match self {
+            Self::Stdin => stdio::dup2_stdin(grip)?,
+            Self::Stdout => stdio::dup2_stdout(grip)?,
+            Self::Stderr => stdio::dup2_stderr(grip)?,
+        }
+        flushed
+    }
+}
+
+/// Flushes `std_stream`, and C's `stdio` buffers, for C libraries which use
+/// `printf` and friends.
+fn flush_all(mut std_stream: impl Write) -> io::Result<()> {
+    let flushed = std_stream.flush();
+    unsafe {
+        libc::fflush(null_mut());
+    }
+    flushed
+}
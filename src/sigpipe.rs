@@ -0,0 +1,234 @@
+//! Writing to pipes and sockets without raising `SIGPIPE`.
+//!
+//! On Posix-ish platforms, writing to a pipe or socket whose reading end has
+//! been closed raises `SIGPIPE`, which terminates the process unless the
+//! whole program ignores it. [`NoSigpipe`] suppresses the signal for the
+//! writes it performs, so that they fail with [`io::ErrorKind::BrokenPipe`]
+//! instead.
+
+#[cfg(not(unix))]
+use crate::borrowed::BorrowedWriteable;
+use crate::grip::AsGrip;
+use std::io::{self, IoSlice, Write};
+#[cfg(unix)]
+use {::rustix::io::Errno, io_lifetimes::BorrowedFd, std::mem::MaybeUninit, std::ptr::null_mut};
+
+/// Adapts a writeable grip so that writes which would raise `SIGPIPE`
+/// instead fail with [`io::ErrorKind::BrokenPipe`].
+///
+/// Only the writes performed through this adapter are affected; the
+/// process' signal dispositions are left unchanged.
+///
+/// # Platform-specific behavior
+///
+/// On Linux and Android, writes to sockets use `MSG_NOSIGNAL`. Other writes
+/// on Posix-ish platforms block `SIGPIPE` in the current thread for the
+/// duration of the write, and consume any `SIGPIPE` the write raised before
+/// unblocking it. On other platforms, writes don't raise signals, and this
+/// writes to the grip directly.
+#[derive(Debug)]
+pub struct NoSigpipe<W> {
+    inner: W,
+
+    /// Whether `inner` is a socket, determined on the first write.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    is_socket: Option<bool>,
+}
+
+impl<W: AsGrip> NoSigpipe<W> {
+    /// Wraps `inner` so that writes to it don't raise `SIGPIPE`.
+    #[inline]
+    pub const fn new(inner: W) -> Self {
+        Self {
+            inner,
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            is_socket: None,
+        }
+    }
+
+    /// Returns a reference to the underlying writer.
+    #[inline]
+    pub const fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Consumes this `NoSigpipe`, returning the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(unix)]
+impl<W: AsGrip> Write for NoSigpipe<W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        if self.is_socket() {
+            let fd = self.inner.as_grip();
+            return Ok(::rustix::net::send(
+                fd,
+                buf,
+                ::rustix::net::SendFlags::NOSIGNAL,
+            )?);
+        }
+
+        let fd = self.inner.as_grip();
+
+        with_sigpipe_blocked(fd, |fd| ::rustix::io::write(fd, buf))
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        if self.is_socket() {
+            let fd = self.inner.as_grip();
+            return Ok(::rustix::net::sendmsg(
+                fd,
+                bufs,
+                &mut Default::default(),
+                ::rustix::net::SendFlags::NOSIGNAL,
+            )?);
+        }
+
+        let fd = self.inner.as_grip();
+
+        with_sigpipe_blocked(fd, |fd| ::rustix::io::writev(fd, bufs))
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        // Writes go directly to the grip, so there's nothing to flush.
+        Ok(())
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl<W: AsGrip> NoSigpipe<W> {
+    /// Tests whether the grip is a socket, which can be written to with
+    /// `MSG_NOSIGNAL`, checking only on the first call.
+    fn is_socket(&mut self) -> bool {
+        use ::rustix::fs::{fstat, FileType};
+
+        let fd = self.inner.as_grip();
+        *self.is_socket.get_or_insert_with(|| {
+            // If `fstat` fails, the write will fail too, so it doesn't
+            // matter which path it takes.
+            fstat(fd)
+                .is_ok_and(|stat| FileType::from_raw_mode(stat.st_mode as _) == FileType::Socket)
+        })
+    }
+}
+
+#[cfg(not(unix))]
+impl<W: AsGrip> Write for NoSigpipe<W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writeable().write(buf)
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.writeable().write_vectored(bufs)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.writeable().flush()
+    }
+}
+
+#[cfg(not(unix))]
+impl<W: AsGrip> NoSigpipe<W> {
+    fn writeable(&self) -> BorrowedWriteable<'_> {
+        BorrowedWriteable::borrow(self.inner.as_grip())
+    }
+}
+
+/// Tests whether `err` indicates that the other end of a pipe, socket, or
+/// terminal has gone away.
+///
+/// This recognizes [`io::ErrorKind::BrokenPipe`],
+/// [`io::ErrorKind::ConnectionReset`], [`io::ErrorKind::ConnectionAborted`],
+/// and [`io::ErrorKind::NotConnected`]. On Posix-ish platforms, it also
+/// recognizes `EIO`, which is how terminals report that they've been hung
+/// up.
+pub fn is_peer_gone(err: &io::Error) -> bool {
+    match err.kind() {
+        io::ErrorKind::BrokenPipe
+        | io::ErrorKind::ConnectionReset
+        | io::ErrorKind::ConnectionAborted
+        | io::ErrorKind::NotConnected => true,
+        #[cfg(unix)]
+        _ => err.raw_os_error() == Some(libc::EIO),
+        #[cfg(not(unix))]
+        _ => false,
+    }
+}
+
+/// Calls `f` with `SIGPIPE` blocked in the current thread, and consumes any
+/// `SIGPIPE` it raises.
+#[cfg(unix)]
+fn with_sigpipe_blocked<'a>(
+    fd: BorrowedFd<'a>,
+    f: impl FnOnce(BorrowedFd<'a>) -> Result<usize, Errno>,
+) -> io::Result<usize> {
+    unsafe {
+        let mut sigpipe = MaybeUninit::<libc::sigset_t>::uninit();
+        libc::sigemptyset(sigpipe.as_mut_ptr());
+        libc::sigaddset(sigpipe.as_mut_ptr(), libc::SIGPIPE);
+        let sigpipe = sigpipe.assume_init();
+
+        // If a `SIGPIPE` is already pending, we can't tell it apart from one
+        // raised by our write, so leave it, and the signal mask, alone.
+        if sigpipe_is_pending() {
+            return Ok(f(fd)?);
+        }
+
+        let mut old_mask = MaybeUninit::<libc::sigset_t>::uninit();
+        let err = libc::pthread_sigmask(libc::SIG_BLOCK, &sigpipe, old_mask.as_mut_ptr());
+        if err != 0 {
+            return Err(io::Error::from_raw_os_error(err));
+        }
+
+        let result = f(fd);
+
+        if result == Err(Errno::PIPE) {
+            consume_sigpipe(&sigpipe);
+        }
+
+        libc::pthread_sigmask(libc::SIG_SETMASK, old_mask.as_ptr(), null_mut());
+
+        Ok(result?)
+    }
+}
+
+#[cfg(unix)]
+unsafe fn sigpipe_is_pending() -> bool {
+    let mut pending = MaybeUninit::<libc::sigset_t>::uninit();
+    libc::sigpending(pending.as_mut_ptr()) == 0
+        && libc::sigismember(pending.as_ptr(), libc::SIGPIPE) == 1
+}
+
+/// Consumes a pending `SIGPIPE`, without waiting if there isn't one.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+unsafe fn consume_sigpipe(sigpipe: &libc::sigset_t) {
+    let zero = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    while libc::sigtimedwait(sigpipe, null_mut(), &zero) == -1
+        && io::Error::last_os_error().kind() == io::ErrorKind::Interrupted
+    {}
+}
+
+/// Consumes a pending `SIGPIPE`, without waiting if there isn't one.
+#[cfg(all(unix, not(any(target_os = "android", target_os = "linux"))))]
+unsafe fn consume_sigpipe(sigpipe: &libc::sigset_t) {
+    // This platform lacks `sigtimedwait`, so only call `sigwait` if we know
+    // it won't block.
+    if sigpipe_is_pending() {
+        let mut sig = 0;
+        libc::sigwait(sigpipe, &mut sig);
+    }
+}
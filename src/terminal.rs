@@ -0,0 +1,87 @@
+//! Terminal detection, size, and mode control for any grip.
+//!
+//! Prompting for a password should work even when stdin and stdout are
+//! redirected. [`open_controlling_terminal`] opens the process' terminal
+//! directly, and [`RawModeGuard::no_echo`] turns off echoing until the
+//! prompt is done.
+
+use crate::grip::{AsGrip, BorrowedGrip};
+use crate::owned::OwnedReadWrite;
+use crate::pty::WindowSize;
+use ::rustix::fs::{open, Mode, OFlags};
+use ::rustix::termios::{isatty, tcgetattr, tcsetattr, LocalModes, OptionalActions, Termios};
+use std::io;
+
+/// Tests whether `grip` is a terminal.
+#[inline]
+pub fn is_terminal<Grip: AsGrip + ?Sized>(grip: &Grip) -> bool {
+    isatty(grip.as_grip())
+}
+
+/// Returns the window size of the terminal `grip`.
+///
+/// This fails if `grip` isn't a terminal.
+#[inline]
+pub fn terminal_size<Grip: AsGrip + ?Sized>(grip: &Grip) -> io::Result<WindowSize> {
+    crate::pty::window_size(&grip.as_grip())
+}
+
+/// Opens the process' controlling terminal, `/dev/tty`, for reading and
+/// writing, with close-on-exec set.
+///
+/// This works even when stdin and stdout are redirected, and fails if the
+/// process has no controlling terminal.
+pub fn open_controlling_terminal() -> io::Result<OwnedReadWrite> {
+    let fd = open(
+        "/dev/tty",
+        OFlags::RDWR | OFlags::NOCTTY | OFlags::CLOEXEC,
+        Mode::empty(),
+    )?;
+    Ok(OwnedReadWrite::from_grip(fd))
+}
+
+/// A terminal in raw or no-echo mode, which restores the terminal's
+/// original mode when dropped, including when unwinding from a panic.
+#[derive(Debug)]
+#[must_use = "the terminal's mode is restored when the guard is dropped"]
+pub struct RawModeGuard<'a> {
+    grip: BorrowedGrip<'a>,
+    original: Termios,
+}
+
+impl<'a> RawModeGuard<'a> {
+    /// Puts the terminal `grip` into raw mode, in which input is available
+    /// byte by byte, without echoing or line editing, and control
+    /// characters such as Ctrl-C don't generate signals.
+    pub fn raw<Grip: AsGrip + ?Sized>(grip: &'a Grip) -> io::Result<Self> {
+        Self::new(grip.as_grip(), Termios::make_raw)
+    }
+
+    /// Turns off echoing on the terminal `grip`, except for newlines, for
+    /// reading passwords. Input is otherwise unchanged, and is still
+    /// available line by line.
+    pub fn no_echo<Grip: AsGrip + ?Sized>(grip: &'a Grip) -> io::Result<Self> {
+        Self::new(grip.as_grip(), |termios| {
+            termios.local_modes -= LocalModes::ECHO;
+            termios.local_modes |= LocalModes::ECHONL;
+        })
+    }
+
+    fn new(grip: BorrowedGrip<'a>, modify: impl FnOnce(&mut Termios)) -> io::Result<Self> {
+        let original = tcgetattr(grip)?;
+        let mut termios = original.clone();
+        modify(&mut termios);
+        // Discard any input typed before the mode change, which the user
+        // may have typed expecting it to be echoed.
+        tcsetattr(grip, OptionalActions::Flush, &termios)?;
+        Ok(Self { grip, original })
+    }
+}
+
+impl Drop for RawModeGuard<'_> {
+    fn drop(&mut self) {
+        // There's no way to report an error from `drop`, and the terminal is
+        // left as it is if this fails.
+        let _ = tcsetattr(self.grip, OptionalActions::Drain, &self.original);
+    }
+}
@@ -0,0 +1,154 @@
+//! Vectored I/O helpers which work on stable Rust.
+//!
+//! [`Write::write_all_vectored`] and the `is_*_vectored` functions in
+//! [`Read`] and [`Write`] are currently unstable. These functions provide
+//! the same functionality on stable Rust.
+//!
+//! [`Write::write_all_vectored`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_all_vectored
+
+use crate::grip::AsGrip;
+use std::io::{self, IoSlice, IoSliceMut, Read, Write};
+use std::mem::take;
+use std::slice;
+
+/// Writes all of the data in `bufs` to `writer`, retrying after short
+/// writes.
+///
+/// On return, the contents of `bufs` are unspecified.
+pub fn write_all_vectored<W: Write + ?Sized>(
+    writer: &mut W,
+    mut bufs: &mut [IoSlice<'_>],
+) -> io::Result<()> {
+    // Skip any leading empty buffers.
+    advance_slices(&mut bufs, 0);
+    while !bufs.is_empty() {
+        match writer.write_vectored(bufs) {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write whole buffer",
+                ));
+            }
+            Ok(n) => advance_slices(&mut bufs, n),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Reads from `reader` until all of `bufs` are filled, retrying after short
+/// reads.
+///
+/// On return, the contents of `bufs` are unspecified.
+pub fn read_exact_vectored<R: Read + ?Sized>(
+    reader: &mut R,
+    mut bufs: &mut [IoSliceMut<'_>],
+) -> io::Result<()> {
+    // Skip any leading empty buffers.
+    advance_slices_mut(&mut bufs, 0);
+    while !bufs.is_empty() {
+        match reader.read_vectored(bufs) {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "failed to fill whole buffer",
+                ));
+            }
+            Ok(n) => advance_slices_mut(&mut bufs, n),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Tests whether reads from `grip` have an efficient vectored
+/// implementation.
+///
+/// If this returns `false`, [`Read::read_vectored`] only reads into the first
+/// non-empty buffer at a time.
+#[cfg(not(windows))]
+#[inline]
+pub fn is_read_vectored<Grip: AsGrip>(_grip: &Grip) -> bool {
+    true
+}
+
+/// Tests whether reads from `grip` have an efficient vectored
+/// implementation.
+///
+/// If this returns `false`, [`Read::read_vectored`] only reads into the first
+/// non-empty buffer at a time.
+#[cfg(windows)]
+#[inline]
+pub fn is_read_vectored<Grip: AsGrip>(grip: &Grip) -> bool {
+    grip.as_grip().as_socket().is_some()
+}
+
+/// Tests whether writes to `grip` have an efficient vectored
+/// implementation.
+///
+/// If this returns `false`, [`Write::write_vectored`] only writes the first
+/// non-empty buffer at a time.
+#[cfg(not(windows))]
+#[inline]
+pub fn is_write_vectored<Grip: AsGrip>(_grip: &Grip) -> bool {
+    true
+}
+
+/// Tests whether writes to `grip` have an efficient vectored
+/// implementation.
+///
+/// If this returns `false`, [`Write::write_vectored`] only writes the first
+/// non-empty buffer at a time.
+#[cfg(windows)]
+#[inline]
+pub fn is_write_vectored<Grip: AsGrip>(grip: &Grip) -> bool {
+    grip.as_grip().as_socket().is_some()
+}
+
+/// Like `IoSlice::advance_slices`, which requires Rust 1.81.
+fn advance_slices(bufs: &mut &mut [IoSlice<'_>], n: usize) {
+    let (remove, skip) = split_point(bufs.iter().map(|buf| buf.len()), n);
+    *bufs = &mut take(bufs)[remove..];
+    if let Some(first) = bufs.first_mut() {
+        // Safety: This produces a subslice of the memory `first` already
+        // borrows, with the same lifetime.
+        *first = IoSlice::new(unsafe {
+            slice::from_raw_parts(first.as_ptr().add(skip), first.len() - skip)
+        });
+    } else {
+        assert_eq!(skip, 0, "advancing io slices beyond their length");
+    }
+}
+
+/// Like `IoSliceMut::advance_slices`, which requires Rust 1.81.
+fn advance_slices_mut(bufs: &mut &mut [IoSliceMut<'_>], n: usize) {
+    let (remove, skip) = split_point(bufs.iter().map(|buf| buf.len()), n);
+    *bufs = &mut take(bufs)[remove..];
+    if let Some(first) = bufs.first_mut() {
+        // Safety: This produces a subslice of the memory `first` already
+        // exclusively borrows, with the same lifetime, and replaces `first`
+        // with it.
+        *first = IoSliceMut::new(unsafe {
+            slice::from_raw_parts_mut(first.as_mut_ptr().add(skip), first.len() - skip)
+        });
+    } else {
+        assert_eq!(skip, 0, "advancing io slices beyond their length");
+    }
+}
+
+/// Returns the number of whole buffers covered by the first `n` bytes, and
+/// the number of bytes of the following buffer to skip.
+fn split_point(lens: impl Iterator<Item = usize>, n: usize) -> (usize, usize) {
+    let mut remove = 0;
+    let mut accumulated = 0;
+    for len in lens {
+        if accumulated + len > n {
+            break;
+        }
+        accumulated += len;
+        remove += 1;
+    }
+    (remove, n - accumulated)
+}
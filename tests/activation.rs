@@ -0,0 +1,191 @@
+//! Tests for systemd socket activation.
+
+#![cfg(unix)]
+
+use io_extras::activation::take_activated_grips;
+use io_extras::command::CommandGripExt;
+use io_extras::grip::GripKind;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::os::unix::io::AsRawFd;
+use std::process::{Command, Stdio};
+
+const CHILD_VAR: &str = "IO_EXTRAS_TEST_ACTIVATION_CHILD";
+
+/// Re-runs this test binary as an activated service, running only the test
+/// `name`. `LISTEN_PID` must be the service's own pid, so set it from a shell
+/// which then `exec`s.
+fn run_activated(name: &str, fds: &str, names: &str, command: &mut Command) -> io::Result<()> {
+    let status = command
+        .arg("-c")
+        .arg("LISTEN_PID=$$ exec \"$0\" --exact \"$1\" --nocapture")
+        .arg(std::env::current_exe()?)
+        .arg(name)
+        .env(CHILD_VAR, name)
+        .env("LISTEN_FDS", fds)
+        .env("LISTEN_FDNAMES", names)
+        .stdout(Stdio::null())
+        .status()?;
+    assert!(status.success());
+    Ok(())
+}
+
+fn is_child(name: &str) -> bool {
+    std::env::var_os(CHILD_VAR).is_some_and(|var| var == name)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // process spawning calls foreign functions
+fn activation() -> io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let udp = UdpSocket::bind("127.0.0.1:0")?;
+    run_activated(
+        "activated",
+        "2",
+        "web:stats",
+        Command::new("sh").grip(3, &listener).grip(4, &udp),
+    )
+}
+
+#[test]
+fn activated() -> io::Result<()> {
+    if !is_child("activated") {
+        return Ok(());
+    }
+
+    let grips = take_activated_grips()?;
+    assert_eq!(grips.len(), 2);
+    assert_eq!(grips[0].name(), "web");
+    assert_eq!(grips[0].kind()?, GripKind::TcpListener);
+    assert_eq!(grips[1].name(), "stats");
+    assert_eq!(grips[1].kind()?, GripKind::UdpSocket);
+
+    // The variables are unset, and the grips are only taken once.
+    assert!(std::env::var_os("LISTEN_PID").is_none());
+    assert!(std::env::var_os("LISTEN_FDS").is_none());
+    assert!(std::env::var_os("LISTEN_FDNAMES").is_none());
+    assert!(take_activated_grips()?.is_empty());
+
+    let mut grips = grips.into_iter();
+    let listener = grips.next().unwrap().into_tcp_listener()?;
+    let flags = unsafe { libc::fcntl(listener.as_raw_fd(), libc::F_GETFD) };
+    assert_ne!(flags & libc::FD_CLOEXEC, 0);
+
+    // A UDP socket isn't a TCP listener.
+    let err = grips.next().unwrap().into_tcp_listener().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    // Prove the listener works by accepting a connection from ourselves.
+    let mut client = TcpStream::connect(listener.local_addr()?)?;
+    let (mut server, _) = listener.accept()?;
+    server.write_all(b"hello")?;
+    drop(server);
+    let mut buf = String::new();
+    client.read_to_string(&mut buf)?;
+    assert_eq!(buf, "hello");
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // process spawning calls foreign functions
+fn activation_without_fds() -> io::Result<()> {
+    run_activated("activated_without_fds", "0", "", &mut Command::new("sh"))
+}
+
+#[test]
+fn activated_without_fds() -> io::Result<()> {
+    if !is_child("activated_without_fds") {
+        return Ok(());
+    }
+
+    // An empty `LISTEN_FDNAMES` has no names, which matches `LISTEN_FDS=0`.
+    assert!(take_activated_grips()?.is_empty());
+    assert!(std::env::var_os("LISTEN_FDS").is_none());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // process spawning calls foreign functions
+fn activation_retry() -> io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    run_activated(
+        "activated_retry",
+        "1",
+        "web:extra",
+        Command::new("sh").grip(3, &listener),
+    )
+}
+
+#[test]
+fn activated_retry() -> io::Result<()> {
+    if !is_child("activated_retry") {
+        return Ok(());
+    }
+
+    // A failed call leaves everything in place, and fails again.
+    for _ in 0..2 {
+        let err = take_activated_grips().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(std::env::var_os("LISTEN_FDS").is_some());
+    }
+
+    // Once the environment is corrected, the grips can be taken.
+    std::env::set_var("LISTEN_FDNAMES", "web");
+    let grips = take_activated_grips()?;
+    assert_eq!(grips.len(), 1);
+    assert_eq!(grips[0].name(), "web");
+    assert!(std::env::var_os("LISTEN_FDS").is_none());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // fstat and getsockopt call foreign functions
+fn grip_kinds() -> io::Result<()> {
+    use io_extras::grip::kind;
+    use std::os::unix::net::{UnixDatagram, UnixStream};
+
+    let (reader, _writer) = os_pipe::pipe()?;
+    assert_eq!(kind(&reader)?, GripKind::Pipe);
+    assert_eq!(kind(&std::fs::File::open("Cargo.toml")?)?, GripKind::File);
+    assert_eq!(kind(&std::fs::File::open(".")?)?, GripKind::Directory);
+
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let stream = TcpStream::connect(listener.local_addr()?)?;
+    assert_eq!(kind(&listener)?, GripKind::TcpListener);
+    assert_eq!(kind(&stream)?, GripKind::TcpStream);
+    assert_eq!(kind(&UdpSocket::bind("127.0.0.1:0")?)?, GripKind::UdpSocket);
+    assert_eq!(kind(&UnixStream::pair()?.0)?, GripKind::UnixStream);
+    assert_eq!(kind(&UnixDatagram::pair()?.0)?, GripKind::UnixDatagram);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // process spawning calls foreign functions
+fn activation_closed_fd() -> io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    run_activated(
+        "activated_closed_fd",
+        "2",
+        "web:missing",
+        Command::new("sh").grip(3, &listener),
+    )
+}
+
+#[test]
+fn activated_closed_fd() -> io::Result<()> {
+    if !is_child("activated_closed_fd") {
+        return Ok(());
+    }
+
+    // Fd 4 wasn't passed, so taking fails, and leaves fd 3 open and
+    // unclaimed.
+    assert!(take_activated_grips().is_err());
+    assert!(std::env::var_os("LISTEN_FDS").is_some());
+
+    std::env::set_var("LISTEN_FDS", "1");
+    std::env::set_var("LISTEN_FDNAMES", "web");
+    let grips = take_activated_grips()?;
+    assert_eq!(grips.len(), 1);
+    assert_eq!(grips[0].kind()?, GripKind::TcpListener);
+    Ok(())
+}
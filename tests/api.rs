@@ -78,6 +78,7 @@ impl Stream {
 
 #[test]
 #[cfg_attr(miri, ignore)] // TCP I/O calls foreign functions
+#[allow(clippy::let_unit_value)]
 fn likes() {
     let _ = Stream::use_socket(std::net::TcpListener::bind("127.0.0.1:0").unwrap());
     let _ = Stream::use_file(std::fs::File::open("Cargo.toml").unwrap());
@@ -0,0 +1,74 @@
+//! Tests for fd-level stdio capture.
+
+#![cfg(unix)]
+
+use io_extras::capture::capture_stdio;
+use io_extras::grip::{AsRawGrip, FromRawGrip};
+use io_extras::raw::{RawReadable, RawWriteable};
+use std::io::{self, stderr, stdin, stdout, Read, Write};
+
+// The test harness writes its own output to fd 1 as other tests finish, so
+// keep everything which captures in one test.
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn capture() -> io::Result<()> {
+    // Write the way `examples/hello.rs` does.
+    let captured = capture_stdio(b"", || -> io::Result<()> {
+        let stdout = stdout();
+        let stdout = stdout.lock();
+        writeln!(
+            unsafe { RawWriteable::from_raw_grip(stdout.as_raw_grip()) },
+            "hello, world"
+        )?;
+        write!(
+            unsafe { RawWriteable::from_raw_grip(stderr().as_raw_grip()) },
+            "goodbye"
+        )
+    })?;
+    captured.value?;
+    assert_eq!(captured.stdout, b"hello, world\n");
+    assert_eq!(captured.stderr, b"goodbye");
+
+    // Feed stdin, and echo it back.
+    let captured = capture_stdio(b"echo", || -> io::Result<String> {
+        let mut input = String::new();
+        unsafe { RawReadable::from_raw_grip(stdin().as_raw_grip()) }.read_to_string(&mut input)?;
+        write!(
+            unsafe { RawWriteable::from_raw_grip(stdout().as_raw_grip()) },
+            "{}",
+            input
+        )?;
+        Ok(input)
+    })?;
+    assert_eq!(captured.value?, "echo");
+    assert_eq!(captured.stdout, b"echo");
+
+    // Output and input larger than a pipe buffer don't deadlock, and input
+    // doesn't need to be read.
+    let big = vec![b'x'; 1 << 20];
+    let captured = capture_stdio(&big, || {
+        unsafe { RawWriteable::from_raw_grip(stdout().as_raw_grip()) }.write_all(&big)
+    })?;
+    captured.value?;
+    assert_eq!(captured.stdout, big);
+
+    // Concurrent captures are serialized.
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            std::thread::spawn(move || {
+                capture_stdio(b"", || {
+                    let mut out = unsafe { RawWriteable::from_raw_grip(stdout().as_raw_grip()) };
+                    for _ in 0..100 {
+                        write!(out, "{}", i).unwrap();
+                    }
+                })
+                .unwrap()
+                .stdout
+            })
+        })
+        .collect();
+    for (i, thread) in threads.into_iter().enumerate() {
+        assert_eq!(thread.join().unwrap(), i.to_string().repeat(100).as_bytes());
+    }
+    Ok(())
+}
@@ -0,0 +1,177 @@
+//! Tests for typed channels which carry grips.
+
+#![cfg(all(unix, not(target_vendor = "apple")))]
+
+use io_extras::channel::{channel, Message, DEFAULT_MAX_MESSAGE_SIZE, MAX_GRIPS};
+use io_extras::grip::{AsGrip, FromGrip};
+use io_extras::passing::send_grips;
+use std::fs::File;
+use std::io::{self, Read, Write};
+
+#[derive(Debug, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl Message for Point {
+    fn encode(&self, buf: &mut Vec<u8>) -> io::Result<()> {
+        buf.extend_from_slice(&self.x.to_le_bytes());
+        buf.extend_from_slice(&self.y.to_le_bytes());
+        Ok(())
+    }
+
+    fn decode(bytes: &[u8]) -> io::Result<Self> {
+        let bytes: [u8; 8] = bytes
+            .try_into()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "bad point"))?;
+        Ok(Self {
+            x: i32::from_le_bytes(bytes[..4].try_into().unwrap()),
+            y: i32::from_le_bytes(bytes[4..].try_into().unwrap()),
+        })
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // sendmsg and recvmsg call foreign functions
+fn messages_with_grips() -> io::Result<()> {
+    let (mut sender, mut receiver) = channel::<String>()?;
+    let (mut reader, writer) = os_pipe::pipe()?;
+
+    sender.send(&"here's a pipe".to_owned(), &[writer.as_grip()])?;
+    sender.send(&"and nothing".to_owned(), &[])?;
+    drop(writer);
+
+    let (value, grips) = receiver.recv()?.unwrap();
+    assert_eq!(value, "here's a pipe");
+    assert_eq!(grips.len(), 1);
+    let mut file = File::from_grip(grips.into_iter().next().unwrap());
+    file.write_all(b"hello")?;
+    drop(file);
+    let mut buf = String::new();
+    reader.read_to_string(&mut buf)?;
+    assert_eq!(buf, "hello");
+
+    let (value, grips) = receiver.recv()?.unwrap();
+    assert_eq!(value, "and nothing");
+    assert!(grips.is_empty());
+
+    drop(sender);
+    assert!(receiver.recv()?.is_none());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // sendmsg and recvmsg call foreign functions
+fn large_messages() -> io::Result<()> {
+    let (mut sender, mut receiver) = channel::<Vec<u8>>()?;
+    let big: Vec<u8> = (0..1_000_000_u32).map(|i| i as u8).collect();
+
+    let expected = big.clone();
+    let thread = std::thread::spawn(move || -> io::Result<()> {
+        for _ in 0..3 {
+            let (value, _) = receiver.recv()?.unwrap();
+            assert_eq!(value, expected);
+        }
+        Ok(())
+    });
+    for _ in 0..3 {
+        sender.send(&big, &[])?;
+    }
+    thread.join().unwrap()
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // sendmsg and recvmsg call foreign functions
+fn custom_messages() -> io::Result<()> {
+    let (mut sender, mut receiver) = channel::<Point>()?;
+    sender.send(&Point { x: 3, y: -4 }, &[])?;
+    assert_eq!(receiver.recv()?.unwrap().0, Point { x: 3, y: -4 });
+
+    let file = File::open("Cargo.toml")?;
+    let grips = vec![file.as_grip(); MAX_GRIPS + 1];
+    let err = sender.send(&Point { x: 0, y: 0 }, &grips).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    // A closed receiver makes sending fail, rather than raising `SIGPIPE`.
+    drop(receiver);
+    let err = sender.send(&Point { x: 0, y: 0 }, &[]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // sendmsg and recvmsg call foreign functions
+fn message_size_limit() -> io::Result<()> {
+    let (mut sender, mut receiver) = channel::<Vec<u8>>()?;
+    assert_eq!(sender.max_message_size(), DEFAULT_MAX_MESSAGE_SIZE);
+    assert_eq!(receiver.max_message_size(), DEFAULT_MAX_MESSAGE_SIZE);
+
+    sender.set_max_message_size(16);
+    let err = sender.send(&vec![0; 17], &[]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    sender.send(&vec![0; 16], &[])?;
+    assert_eq!(receiver.recv()?.unwrap().0, vec![0; 16]);
+
+    // A header claiming a huge message is rejected before allocating.
+    let socket = io_lifetimes::OwnedFd::from(sender);
+    send_grips(&socket, &header(u32::MAX), &[])?;
+    let err = receiver.recv().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    Ok(())
+}
+
+fn header(len: u32) -> [u8; 8] {
+    let mut header = [0_u8; 8];
+    header[..4].copy_from_slice(&len.to_le_bytes());
+    header
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // sendmsg and recvmsg call foreign functions
+fn poisoned_after_partial_message() -> io::Result<()> {
+    let (sender, mut receiver) = channel::<()>()?;
+    let socket = io_lifetimes::OwnedFd::from(sender);
+
+    // A message which fails to decode, but was read completely, leaves the
+    // receiver usable.
+    send_grips(&socket, &header(1), &[])?;
+    send_grips(&socket, b"x", &[])?;
+    let err = receiver.recv().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    send_grips(&socket, &header(0), &[])?;
+    assert!(receiver.recv()?.is_some());
+
+    // A message whose data is shorter than its header says leaves the
+    // receiver unable to tell where the next message starts.
+    send_grips(&socket, &header(10), &[])?;
+    send_grips(&socket, b"short", &[])?;
+    send_grips(&socket, &header(0), &[])?;
+    let err = receiver.recv().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let err = receiver.recv().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "serde", feature = "bincode"))]
+#[cfg_attr(miri, ignore)] // sendmsg and recvmsg call foreign functions
+fn serde_messages() -> io::Result<()> {
+    use io_extras::channel::Serde;
+
+    let (mut sender, mut receiver) = channel::<Serde<(String, Vec<u32>)>>()?;
+    let value = ("hello".to_owned(), vec![1, 2, 3]);
+    sender.send(&Serde(value.clone()), &[])?;
+    let (message, grips) = receiver.recv()?.unwrap();
+    assert_eq!(message.0, value);
+    assert!(grips.is_empty());
+
+    // Trailing bytes are rejected.
+    let mut buf = Vec::new();
+    Serde(value).encode(&mut buf)?;
+    buf.push(0);
+    let err = <Serde<(String, Vec<u32>)>>::decode(&buf).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! Tests for explicit closing and syncing.
+
+#![cfg(any(unix, windows))]
+
+use io_extras::grip::{close, close_abortive, FromGrip, IntoGrip, OwnedGrip};
+use io_extras::owned::{OwnedReadable, OwnedWriteable};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+#[cfg(any(not(windows), feature = "os_pipe"))]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn pipe_close() -> io::Result<()> {
+    let (reader, writer) = os_pipe::pipe()?;
+    let mut writeable = OwnedWriteable::from_grip(writer.into_grip());
+    writeable.write_all(b"hello")?;
+    writeable.close()?;
+
+    let mut buf = String::new();
+    let mut readable = OwnedReadable::from_grip(reader.into_grip());
+    readable.read_to_string(&mut buf)?;
+    assert_eq!(buf, "hello");
+    readable.close()
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // file I/O calls foreign functions
+fn owned_into_grip() -> io::Result<()> {
+    // Converting back into an `OwnedGrip` must not close the handle.
+    let readable = OwnedReadable::from_grip(File::open("Cargo.toml")?.into_grip());
+    let grip: OwnedGrip = readable.into();
+    let mut buf = String::new();
+    OwnedReadable::from_grip(grip).read_to_string(&mut buf)?;
+    assert!(buf.contains("io-extras"));
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // file I/O calls foreign functions
+fn owned_writeable_into_grip() -> io::Result<()> {
+    // Converting back into an `OwnedGrip` must not close the handle.
+    let path = std::env::temp_dir().join("io-extras-test-owned-writeable-into-grip");
+    let writeable = OwnedWriteable::from_grip(File::create(&path)?.into_grip());
+    let grip: OwnedGrip = writeable.into();
+    OwnedWriteable::from_grip(grip).write_all(b"hello")?;
+    assert_eq!(std::fs::read_to_string(&path)?, "hello");
+    std::fs::remove_file(path)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // file I/O calls foreign functions
+fn file_sync() -> io::Result<()> {
+    let path = std::env::temp_dir().join("io-extras-test-file-sync");
+    let file = File::create(&path)?;
+    let mut writeable = OwnedWriteable::from_grip(file.into_grip());
+    writeable.write_all(b"hello")?;
+    writeable.sync_data()?;
+    writeable.sync_all()?;
+    writeable.close()?;
+    std::fs::remove_file(path)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // TCP I/O calls foreign functions
+fn tcp_stream_close_abortive() -> io::Result<()> {
+    let listener = TcpListener::bind("localhost:0")?;
+    let client = TcpStream::connect(listener.local_addr()?)?;
+    let mut server = listener.accept()?.0;
+
+    close_abortive(client.into_grip())?;
+    let mut buf = [0_u8; 1];
+    assert_eq!(
+        server.read(&mut buf).unwrap_err().kind(),
+        io::ErrorKind::ConnectionReset
+    );
+
+    // Abortive closes fail on non-sockets, but still close the grip.
+    assert!(close_abortive(File::open("Cargo.toml")?.into_grip()).is_err());
+    close(server.into_grip())
+}
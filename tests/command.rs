@@ -0,0 +1,55 @@
+//! Tests for passing grips to child processes.
+
+#![cfg(unix)]
+
+use io_extras::command::CommandGripExt;
+use std::io::{self, Read};
+use std::process::{Command, Stdio};
+
+#[test]
+#[cfg_attr(miri, ignore)] // process spawning calls foreign functions
+fn grips_at_chosen_numbers() -> io::Result<()> {
+    // Map several pipes to the low numbers that their staged duplicates are
+    // likely to land on too, in reverse order, so that targets and sources
+    // overlap.
+    let targets = [8, 7, 6, 5, 4, 3];
+    let mut readers = Vec::new();
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg("for fd in 3 4 5 6 7 8; do eval \"echo $fd >&$fd\"; done; echo done");
+    for target in targets {
+        let (reader, writer) = os_pipe::pipe()?;
+        command.grip(target, writer);
+        readers.push(reader);
+    }
+
+    let output = command.stderr(Stdio::inherit()).output()?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"done\n");
+
+    // Drop `command` so that the parent's copies of the grips are closed.
+    drop(command);
+    for (target, mut reader) in targets.into_iter().zip(readers) {
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf)?;
+        assert_eq!(buf, format!("{}\n", target));
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // process spawning calls foreign functions
+fn grip_overrides_stdout() -> io::Result<()> {
+    let (mut reader, writer) = os_pipe::pipe()?;
+    let mut command = Command::new("sh");
+    command.arg("-c").arg("echo hello").grip(1, &writer);
+    drop(writer);
+    assert!(command.status()?.success());
+    drop(command);
+
+    let mut buf = String::new();
+    reader.read_to_string(&mut buf)?;
+    assert_eq!(buf, "hello\n");
+    Ok(())
+}
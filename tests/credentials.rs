@@ -0,0 +1,74 @@
+//! Tests for identifying the peers of Unix-domain sockets.
+
+#![cfg(unix)]
+
+use io_extras::credentials::peer_credentials;
+use std::io;
+use std::os::unix::net::UnixStream;
+
+#[test]
+#[cfg_attr(miri, ignore)] // getsockopt calls foreign functions
+fn peer_is_self() -> io::Result<()> {
+    let (left, _right) = UnixStream::pair()?;
+    let credentials = peer_credentials(&left)?;
+    assert_eq!(credentials.uid(), unsafe { libc::geteuid() });
+    assert_eq!(credentials.gid(), unsafe { libc::getegid() });
+    #[cfg(any(target_os = "android", target_os = "linux", target_vendor = "apple"))]
+    assert_eq!(credentials.pid(), Some(std::process::id()));
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // getsockopt calls foreign functions
+fn not_a_socket() -> io::Result<()> {
+    let file = std::fs::File::open("Cargo.toml")?;
+    assert!(peer_credentials(&file).is_err());
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+#[cfg_attr(miri, ignore)] // getsockopt calls foreign functions
+fn peer_pidfd() -> io::Result<()> {
+    use io_extras::credentials::peer_pidfd;
+    use std::os::unix::io::AsRawFd;
+
+    let (left, _right) = UnixStream::pair()?;
+    match peer_pidfd(&left) {
+        Ok(pidfd) => {
+            let flags = unsafe { libc::fcntl(pidfd.as_raw_fd(), libc::F_GETFD) };
+            assert_ne!(flags & libc::FD_CLOEXEC, 0);
+        }
+        Err(err) => assert_eq!(err.kind(), io::ErrorKind::Unsupported),
+    }
+    Ok(())
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+#[cfg_attr(miri, ignore)] // sendmsg and recvmsg call foreign functions
+fn credentials_with_messages() -> io::Result<()> {
+    use io_extras::credentials::set_pass_credentials;
+    use io_extras::grip::AsGrip;
+    use io_extras::passing::{recv_grips_with_credentials, send_grips};
+    use std::os::unix::net::UnixDatagram;
+
+    let (left, right) = UnixDatagram::pair()?;
+    let mut buf = [0_u8; 16];
+
+    send_grips(&left, b"anonymous", &[])?;
+    let (n, _, credentials) = recv_grips_with_credentials(&right, &mut buf, 0)?;
+    assert_eq!(&buf[..n], b"anonymous");
+    assert_eq!(credentials, None);
+
+    set_pass_credentials(&right, true)?;
+    let file = std::fs::File::open("Cargo.toml")?;
+    send_grips(&left, b"signed", &[file.as_grip()])?;
+    let (n, grips, credentials) = recv_grips_with_credentials(&right, &mut buf, 1)?;
+    assert_eq!(&buf[..n], b"signed");
+    assert_eq!(grips.len(), 1);
+    let credentials = credentials.unwrap();
+    assert_eq!(credentials.pid(), Some(std::process::id()));
+    assert_eq!(credentials.uid(), unsafe { libc::geteuid() });
+    Ok(())
+}
@@ -0,0 +1,97 @@
+//! Tests for `OwnedDatagram` and `BorrowedDatagram`.
+
+#![cfg(unix)]
+
+use io_extras::addr::GripAddr;
+use io_extras::datagram::{BorrowedDatagram, OwnedDatagram};
+use io_extras::grip::{AsGrip, IntoGrip};
+use std::io;
+use std::net::UdpSocket;
+use std::os::unix::net::UnixDatagram;
+
+#[test]
+#[cfg_attr(miri, ignore)] // send and recv call foreign functions
+fn boundaries_and_truncation() -> io::Result<()> {
+    let (left, right) = UnixDatagram::pair()?;
+    let left = OwnedDatagram::new(left.into_grip())?;
+    let right = BorrowedDatagram::borrow(right.as_grip())?;
+
+    assert_eq!(left.send(b"first")?, 5);
+    left.send(b"second")?;
+    left.send(b"")?;
+    left.send(b"much too long")?;
+
+    let mut buf = [0_u8; 8];
+    let received = right.recv(&mut buf)?;
+    assert_eq!(&buf[..received.len()], b"first");
+    assert!(!received.is_truncated());
+    let received = right.recv(&mut buf)?;
+    assert_eq!(&buf[..received.len()], b"second");
+    let received = right.recv(&mut buf)?;
+    assert!(received.is_empty());
+    let received = right.recv(&mut buf)?;
+    assert_eq!(&buf[..received.len()], b"much too");
+    assert!(received.is_truncated());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // send and recv call foreign functions
+fn addresses() -> io::Result<()> {
+    let server = UdpSocket::bind("127.0.0.1:0")?;
+    let client = UdpSocket::bind("127.0.0.1:0")?;
+    let server_addr = GripAddr::from(server.local_addr()?);
+    let server = BorrowedDatagram::borrow(server.as_grip())?;
+    let client = BorrowedDatagram::borrow(client.as_grip())?;
+
+    client.send_to(b"ping", &server_addr)?;
+    let mut buf = [0_u8; 16];
+    let (received, from) = server.recv_from(&mut buf)?;
+    assert_eq!(&buf[..received.len()], b"ping");
+    server.send_to(b"pong", &from)?;
+    let (received, from) = client.recv_from(&mut buf)?;
+    assert_eq!(&buf[..received.len()], b"pong");
+    assert_eq!(from, server_addr);
+
+    let (left, _right) = UnixDatagram::pair()?;
+    let err = BorrowedDatagram::borrow(left.as_grip())?
+        .send_to(b"nowhere", &GripAddr::UnixUnnamed)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // sendmmsg and recvmmsg call foreign functions
+fn batches() -> io::Result<()> {
+    let (left, right) = UnixDatagram::pair()?;
+    let left = BorrowedDatagram::borrow(left.as_grip())?;
+    let right = BorrowedDatagram::borrow(right.as_grip())?;
+
+    assert_eq!(left.send_batch(&[b"one", b"two", b"three!"])?, 3);
+
+    let mut bufs = [[0_u8; 5]; 4];
+    let mut slices: Vec<&mut [u8]> = bufs.iter_mut().map(|buf| &mut buf[..]).collect();
+    let received = right.recv_batch(&mut slices)?;
+    assert_eq!(received.len(), 3);
+    assert_eq!(&bufs[0][..received[0].len()], b"one");
+    assert_eq!(&bufs[1][..received[1].len()], b"two");
+    assert_eq!(&bufs[2][..received[2].len()], b"three");
+    assert!(!received[1].is_truncated());
+    assert!(received[2].is_truncated());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // fstat calls foreign functions
+fn not_a_socket() -> io::Result<()> {
+    let file = std::fs::File::open("Cargo.toml")?;
+    let err = BorrowedDatagram::borrow(file.as_grip()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let err = OwnedDatagram::new(file.into_grip()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let socket = OwnedDatagram::from(UdpSocket::bind("127.0.0.1:0")?);
+    assert!(socket.as_datagram().send(b"unconnected").is_err());
+    Ok(())
+}
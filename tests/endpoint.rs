@@ -0,0 +1,99 @@
+//! Tests for opening endpoints.
+
+#![cfg(unix)]
+
+use io_extras::endpoint::Endpoint;
+use std::io::{self, Read, Write};
+use std::os::unix::io::IntoRawFd;
+use std::path::PathBuf;
+
+#[test]
+fn parse() -> io::Result<()> {
+    assert_eq!("-".parse::<Endpoint>()?, Endpoint::Stdio);
+    assert_eq!("fd:7".parse::<Endpoint>()?, Endpoint::Fd(7));
+    assert_eq!(
+        "unix:/run/x.sock".parse::<Endpoint>()?,
+        Endpoint::Unix(PathBuf::from("/run/x.sock"))
+    );
+    assert_eq!(
+        "tcp:[::1]:80".parse::<Endpoint>()?,
+        Endpoint::Tcp("[::1]:80".to_owned())
+    );
+    assert_eq!(
+        "data.txt".parse::<Endpoint>()?,
+        Endpoint::File(PathBuf::from("data.txt"))
+    );
+    assert_eq!(
+        "file:fd:3".parse::<Endpoint>()?,
+        Endpoint::File(PathBuf::from("fd:3"))
+    );
+
+    for bad in [
+        "", "fd:", "fd:x", "fd:-1", "unix:", "tcp:host", "tcp::80", "file:",
+    ] {
+        let err = bad.parse::<Endpoint>().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{:?}", bad);
+    }
+
+    // Displaying an endpoint gives a spec which parses back to it.
+    for spec in ["-", "fd:7", "unix:/a", "tcp:example.com:443", "file:fd:3"] {
+        let endpoint = spec.parse::<Endpoint>()?;
+        assert_eq!(endpoint.to_string().parse::<Endpoint>()?, endpoint);
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // file I/O calls foreign functions
+fn files_and_fds() -> io::Result<()> {
+    let path = std::env::temp_dir().join(format!("io-extras-endpoint-{}", std::process::id()));
+    let endpoint = Endpoint::File(path.clone());
+
+    endpoint.open_writeable()?.write_all(b"hello")?;
+    let mut buf = String::new();
+    endpoint.open_readable()?.read_to_string(&mut buf)?;
+    assert_eq!(buf, "hello");
+
+    // Opening for reading and writing doesn't truncate.
+    let mut both = endpoint.open_read_write()?;
+    buf.clear();
+    both.read_to_string(&mut buf)?;
+    assert_eq!(buf, "hello");
+
+    // Simulate an inherited grip by leaking a file's fd, and clearing
+    // close-on-exec, as a parent passing it across `exec` would.
+    let raw_fd = std::fs::File::open(&path)?.into_raw_fd();
+    assert_eq!(unsafe { libc::fcntl(raw_fd, libc::F_SETFD, 0) }, 0);
+    let endpoint = format!("fd:{}", raw_fd).parse::<Endpoint>()?;
+    buf.clear();
+    endpoint.open_readable()?.read_to_string(&mut buf)?;
+    assert_eq!(buf, "hello");
+
+    let err = "-".parse::<Endpoint>()?.listen().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    std::fs::remove_file(&path)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // socket I/O calls foreign functions
+fn tcp_listen_and_connect() -> io::Result<()> {
+    let listener = "tcp:127.0.0.1:0".parse::<Endpoint>()?.listen()?;
+    let addr = match listener.local_addr()? {
+        io_extras::addr::GripAddr::Inet(addr) => addr,
+        other => panic!("unexpected address {:?}", other),
+    };
+
+    let endpoint = format!("tcp:{}", addr).parse::<Endpoint>()?;
+    let mut client = endpoint.open_read_write()?;
+    let (mut server, _) = listener.accept()?;
+    client.write_all(b"ping")?;
+    let mut buf = [0_u8; 4];
+    server.read_exact(&mut buf)?;
+    assert_eq!(&buf, b"ping");
+
+    drop(server);
+    let mut rest = Vec::new();
+    client.read_to_end(&mut rest)?;
+    assert!(rest.is_empty());
+    Ok(())
+}
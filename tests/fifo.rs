@@ -0,0 +1,73 @@
+//! Tests for creating and opening FIFOs.
+
+#![cfg(unix)]
+
+use io_extras::fifo;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn temp_fifo(name: &str) -> io::Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("io-extras-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    fifo::create(&path, 0o600)?;
+    Ok(path)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // mkfifo and open call foreign functions
+fn timeouts() -> io::Result<()> {
+    let path = temp_fifo("fifo-timeouts")?;
+    let short = Duration::from_millis(20);
+
+    let err = fifo::open_writer_timeout(&path, short).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    let err = fifo::open_reader_timeout(&path, short).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+    // With a reader waiting, a writer opens, and the reader sees its data.
+    let reader_path = path.clone();
+    let reader = std::thread::spawn(move || -> io::Result<String> {
+        let mut reader = fifo::open_reader_timeout(&reader_path, Duration::from_secs(60))?;
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf)?;
+        Ok(buf)
+    });
+    let mut writer = fifo::open_writer_timeout(&path, Duration::from_secs(60))?;
+    writer.write_all(b"in time")?;
+    drop(writer);
+    assert_eq!(reader.join().unwrap()?, "in time");
+
+    std::fs::remove_file(&path)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // mkfifo and open call foreign functions
+fn keep_alive() -> io::Result<()> {
+    let path = temp_fifo("fifo-keep-alive")?;
+
+    // This doesn't block, and lets writers open without blocking.
+    let mut reader = fifo::open_reader_keep_alive(&path)?;
+    fifo::open_writer(&path)?.write_all(b"first ")?;
+    fifo::open_writer(&path)?.write_all(b"second")?;
+
+    // The reader didn't see end-of-file between the writers.
+    let mut buf = [0_u8; 12];
+    reader.read_exact(&mut buf)?;
+    assert_eq!(&buf, b"first second");
+
+    std::fs::remove_file(&path)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // open calls foreign functions
+fn not_a_fifo() {
+    let err = fifo::open_reader("Cargo.toml").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    // A directory is rejected before it's opened, rather than failing to
+    // open for writing.
+    let err = fifo::open_reader_keep_alive("src").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let err = fifo::create("Cargo.toml", 0o600).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+}
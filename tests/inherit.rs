@@ -0,0 +1,95 @@
+//! Tests for passing grips to children by number.
+
+#![cfg(unix)]
+
+use io_extras::command::CommandGripExt;
+use io_extras::grip::FromGrip;
+use io_extras::inherit::{take_grip_from_arg, take_grip_from_env};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, IntoRawFd};
+use std::process::{Command, Stdio};
+
+const ENV_VAR: &str = "IO_EXTRAS_TEST_INHERIT_FD";
+
+#[test]
+#[cfg_attr(miri, ignore)] // process spawning calls foreign functions
+fn pass_to_child() -> io::Result<()> {
+    let (mut arg_reader, arg_writer) = os_pipe::pipe()?;
+    let (mut env_reader, env_writer) = os_pipe::pipe()?;
+
+    // Re-run this test binary as the child. The `fd:N` argument doubles as a
+    // test name filter which matches nothing.
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .args(["--exact", "inherited", "--nocapture"])
+        .grip_arg("fd:", arg_writer)
+        .grip_env(ENV_VAR, env_writer)
+        .stdout(Stdio::null());
+    assert!(command.status()?.success());
+    drop(command);
+
+    let mut buf = String::new();
+    arg_reader.read_to_string(&mut buf)?;
+    assert_eq!(buf, "from arg");
+    buf.clear();
+    env_reader.read_to_string(&mut buf)?;
+    assert_eq!(buf, "from env");
+    Ok(())
+}
+
+#[test]
+fn inherited() -> io::Result<()> {
+    if std::env::var_os(ENV_VAR).is_none() {
+        return Ok(());
+    }
+
+    let arg = std::env::args()
+        .find_map(|arg| arg.strip_prefix("fd:").map(str::to_owned))
+        .unwrap();
+    let mut from_arg = File::from_grip(take_grip_from_arg(&arg)?);
+    from_arg.write_all(b"from arg")?;
+
+    let mut from_env = File::from_grip(take_grip_from_env(ENV_VAR)?);
+    assert!(std::env::var_os(ENV_VAR).is_none());
+    from_env.write_all(b"from env")?;
+
+    // The grips are close-on-exec again, so they don't leak further.
+    let flags = unsafe { libc::fcntl(from_env.as_raw_fd(), libc::F_GETFD) };
+    assert_ne!(flags & libc::FD_CLOEXEC, 0);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // file I/O calls foreign functions
+fn take_validates() -> io::Result<()> {
+    let err = take_grip_from_arg("five").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let err = take_grip_from_arg("1").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let err = take_grip_from_arg("1000000").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+    // A file descriptor owned by a live `File` has close-on-exec set, so it
+    // can't be taken, and the `File` is unaffected.
+    let mut file = File::open("Cargo.toml")?;
+    let err = take_grip_from_arg(&file.as_raw_fd().to_string()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let mut buf = String::new();
+    file.read_to_string(&mut buf)?;
+    assert!(buf.contains("io-extras"));
+
+    // Simulate an inherited grip by leaking a file's fd, and clearing
+    // close-on-exec, as a parent passing it across `exec` would.
+    let raw_fd = file.into_raw_fd();
+    assert_eq!(unsafe { libc::fcntl(raw_fd, libc::F_SETFD, 0) }, 0);
+    let raw_fd = raw_fd.to_string();
+    let grip = take_grip_from_arg(&raw_fd)?;
+    let err = take_grip_from_arg(&raw_fd).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    drop(grip);
+
+    let err = take_grip_from_env("IO_EXTRAS_TEST_UNSET_VAR").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Tests for `GripListener`.
+
+#![cfg(unix)]
+
+use io_extras::addr::GripAddr;
+use io_extras::grip::IntoGrip;
+use io_extras::listener::GripListener;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixListener, UnixStream};
+
+#[test]
+#[cfg_attr(miri, ignore)] // accept calls foreign functions
+fn tcp() -> io::Result<()> {
+    let listener = GripListener::from(TcpListener::bind("127.0.0.1:0")?);
+    let addr = match listener.local_addr()? {
+        GripAddr::Inet(addr) => addr,
+        other => panic!("unexpected address {:?}", other),
+    };
+
+    let mut client = TcpStream::connect(addr)?;
+    let (mut stream, peer) = listener.incoming().next().unwrap()?;
+    assert_eq!(peer, GripAddr::Inet(client.local_addr()?));
+
+    let flags = unsafe { libc::fcntl(stream.read_grip().unwrap().as_raw_fd(), libc::F_GETFD) };
+    assert_ne!(flags & libc::FD_CLOEXEC, 0);
+
+    stream.write_all(b"hello")?;
+    drop(stream);
+    let mut buf = String::new();
+    client.read_to_string(&mut buf)?;
+    assert_eq!(buf, "hello");
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // accept calls foreign functions
+fn unix_nonblocking() -> io::Result<()> {
+    let dir = std::env::temp_dir().join(format!("io-extras-listener-{}", std::process::id()));
+    let _ = std::fs::remove_file(&dir);
+    let listener = GripListener::new(UnixListener::bind(&dir)?.into_grip())?;
+    assert_eq!(listener.local_addr()?, GripAddr::Unix(dir.clone()));
+
+    listener.set_nonblocking(true)?;
+    let err = listener.accept().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+
+    let _client = UnixStream::connect(&dir)?;
+    let (stream, peer) = listener.accept_nonblocking()?;
+    assert_eq!(peer, GripAddr::UnixUnnamed);
+    let flags = unsafe { libc::fcntl(stream.read_grip().unwrap().as_raw_fd(), libc::F_GETFL) };
+    assert_ne!(flags & libc::O_NONBLOCK, 0);
+
+    std::fs::remove_file(&dir)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // getsockopt calls foreign functions
+fn not_a_listener() -> io::Result<()> {
+    let (stream, _) = UnixStream::pair()?;
+    let err = GripListener::new(stream.into_grip()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let err = GripListener::new(std::fs::File::open("Cargo.toml")?.into_grip()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! Tests for anonymous memory-backed files.
+
+#![cfg(any(target_os = "android", target_os = "linux", target_os = "freebsd"))]
+
+use io_extras::grip::{AsGrip, FromGrip};
+use io_extras::memfd;
+use io_extras::os::rustix::{MemfdFlags, SealFlags};
+use io_extras::owned::OwnedReadWrite;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+#[test]
+#[cfg_attr(miri, ignore)] // memfd_create calls foreign functions
+fn sealed_is_immutable() -> io::Result<()> {
+    let mut file = File::from_grip(memfd::create("blob", MemfdFlags::ALLOW_SEALING)?);
+    file.write_all(b"immutable blob")?;
+    assert_eq!(memfd::seals(&file)?, SealFlags::empty());
+
+    memfd::add_seals(&file, memfd::IMMUTABLE)?;
+    let seals = memfd::seals(&file)?;
+    assert!(seals.contains(memfd::IMMUTABLE));
+    assert_eq!(seals, memfd::IMMUTABLE);
+
+    assert!(file.write_all(b"!").is_err());
+    assert!(file.set_len(0).is_err());
+    assert!(memfd::add_seals(&file, SealFlags::empty()).is_err());
+
+    file.seek(SeekFrom::Start(0))?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    assert_eq!(contents, "immutable blob");
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // memfd_create calls foreign functions
+fn without_sealing() -> io::Result<()> {
+    let grip = memfd::create("plain", MemfdFlags::empty())?;
+    assert!(memfd::seals(&grip.as_grip())?.contains(SealFlags::SEAL));
+    assert_eq!(
+        memfd::add_seals(&grip.as_grip(), SealFlags::WRITE)
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::PermissionDenied
+    );
+
+    let mut rw = OwnedReadWrite::from_grip(grip);
+    rw.write_all(b"hello")?;
+    Ok(())
+}
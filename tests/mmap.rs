@@ -0,0 +1,89 @@
+//! Tests for memory mappings and fast reading.
+
+#![cfg(unix)]
+
+use io_extras::mmap::{self, Advice, Mapping, Mode};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+fn temp_file(name: &str, contents: &[u8]) -> io::Result<File> {
+    let mut path = std::env::temp_dir();
+    path.push(format!("io-extras-mmap-{}-{}", name, std::process::id()));
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    std::fs::remove_file(&path)?;
+    file.write_all(contents)?;
+    Ok(file)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // mmap calls foreign functions
+fn modes() -> io::Result<()> {
+    let file = temp_file("modes", b"hello world")?;
+
+    // Safety: This test owns the file, and doesn't modify it while mapped.
+    let mut read_only = unsafe { Mapping::new(&file, Mode::ReadOnly)? };
+    assert_eq!(&*read_only, b"hello world");
+    assert!(read_only.as_mut_slice().is_none());
+    read_only.advise(Advice::Sequential)?;
+    drop(read_only);
+
+    // Safety: As above.
+    let mut private = unsafe { Mapping::new(&file, Mode::Private)? };
+    private.as_mut_slice().unwrap()[..5].copy_from_slice(b"HELLO");
+    assert_eq!(&*private, b"HELLO world");
+    drop(private);
+
+    // Safety: As above.
+    let mut shared = unsafe { Mapping::new(&file, Mode::Shared)? };
+    shared.as_mut_slice().unwrap()[6..].copy_from_slice(b"WORLD");
+    shared.flush()?;
+    drop(shared);
+
+    let mut contents = String::new();
+    (&file).seek(SeekFrom::Start(0))?;
+    (&file).read_to_string(&mut contents)?;
+    assert_eq!(contents, "hello WORLD");
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // mmap calls foreign functions
+fn empty_and_unseekable() -> io::Result<()> {
+    let file = temp_file("empty", b"")?;
+    // Safety: This test owns the file, and doesn't modify it while mapped.
+    let mapping = unsafe { Mapping::new(&file, Mode::ReadOnly)? };
+    assert!(mapping.is_empty());
+    mapping.advise(Advice::WillNeed)?;
+
+    let (reader, _writer) = os_pipe::pipe()?;
+    // Safety: Pipes can't be mapped, so nothing is mapped.
+    let err = unsafe { Mapping::new(&reader, Mode::ReadOnly) }.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // read_to_end calls foreign functions
+fn read_to_end() -> io::Result<()> {
+    let contents = vec![7_u8; 100_000];
+    let mut file = temp_file("read_to_end", &contents)?;
+    file.seek(SeekFrom::Start(10))?;
+
+    let mut buf = b"prefix".to_vec();
+    assert_eq!(mmap::read_to_end(&file, &mut buf)?, contents.len() - 10);
+    assert_eq!(&buf[..6], b"prefix");
+    assert_eq!(&buf[6..], &contents[10..]);
+    assert_eq!(file.stream_position()?, contents.len() as u64);
+
+    let (reader, mut writer) = os_pipe::pipe()?;
+    writer.write_all(b"piped")?;
+    drop(writer);
+    let mut buf = Vec::new();
+    mmap::read_to_end(&reader, &mut buf)?;
+    assert_eq!(buf, b"piped");
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! Tests for passing grips over Unix-domain sockets.
+
+#![cfg(unix)]
+
+use io_extras::grip::{AsGrip, FromGrip};
+use io_extras::passing::{recv_grips, send_grips};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixDatagram, UnixStream};
+
+#[test]
+#[cfg_attr(miri, ignore)] // sendmsg and recvmsg call foreign functions
+fn stream() -> io::Result<()> {
+    let (left, right) = UnixStream::pair()?;
+    let (mut reader, writer) = os_pipe::pipe()?;
+
+    assert_eq!(send_grips(&left, b"pipe", &[writer.as_grip()])?, 4);
+    drop(writer);
+
+    let mut buf = [0_u8; 16];
+    let (n, grips) = recv_grips(&right, &mut buf, 4)?;
+    assert_eq!(&buf[..n], b"pipe");
+    assert_eq!(grips.len(), 1);
+
+    let flags = unsafe { libc::fcntl(grips[0].as_raw_fd(), libc::F_GETFD) };
+    assert_ne!(flags & libc::FD_CLOEXEC, 0);
+
+    let mut received = File::from_grip(grips.into_iter().next().unwrap());
+    received.write_all(b"through the received grip")?;
+    drop(received);
+    let mut contents = String::new();
+    reader.read_to_string(&mut contents)?;
+    assert_eq!(contents, "through the received grip");
+
+    // Plain data arrives without grips.
+    send_grips(&left, b"plain", &[])?;
+    let (n, grips) = recv_grips(&right, &mut buf, 4)?;
+    assert_eq!(&buf[..n], b"plain");
+    assert!(grips.is_empty());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // sendmsg and recvmsg call foreign functions
+fn surplus_grips() -> io::Result<()> {
+    let (left, right) = UnixDatagram::pair()?;
+    let files: Vec<File> = (0..8)
+        .map(|_| File::open("Cargo.toml"))
+        .collect::<io::Result<_>>()?;
+    let grips: Vec<_> = files.iter().map(AsGrip::as_grip).collect();
+
+    send_grips(&left, b"many", &grips)?;
+    let mut buf = [0_u8; 16];
+    let err = recv_grips(&right, &mut buf, 1).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    send_grips(&left, b"many", &grips)?;
+    let err = recv_grips(&right, &mut buf, 0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    // The socket is still usable afterwards.
+    send_grips(&left, b"two", &grips[..2])?;
+    let (n, received) = recv_grips(&right, &mut buf, 2)?;
+    assert_eq!(&buf[..n], b"two");
+    assert_eq!(received.len(), 2);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // sendmsg and recvmsg call foreign functions
+fn truncated_datagram() -> io::Result<()> {
+    let (left, right) = UnixDatagram::pair()?;
+    send_grips(&left, b"too long for the buffer", &[])?;
+    let mut buf = [0_u8; 4];
+    let err = recv_grips(&right, &mut buf, 0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    Ok(())
+}
@@ -0,0 +1,122 @@
+//! Tests for `peek`, `recv_with_flags`, and `Peekable`.
+
+#![cfg_attr(target_os = "wasi", feature(wasi_ext))]
+#![cfg(not(target_os = "wasi"))]
+
+use io_extras::borrowed::BorrowedReadable;
+use io_extras::grip::AsGrip;
+use io_extras::peek::Peekable;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+#[test]
+#[cfg_attr(miri, ignore)] // TCP I/O calls foreign functions
+fn tcp_stream_peek() -> io::Result<()> {
+    let listener = TcpListener::bind("localhost:0")?;
+    let mut client = TcpStream::connect(listener.local_addr()?)?;
+    let mut server = listener.accept()?.0;
+    client.write_all(b"\x16\x03\x01hello")?;
+
+    let mut buf = [0_u8; 3];
+    let readable = BorrowedReadable::borrow(server.as_grip());
+    peek_until_full(|buf| readable.peek(buf), &mut buf)?;
+    assert_eq!(&buf, b"\x16\x03\x01");
+
+    // The peeked bytes are still there to be read.
+    let mut read = [0_u8; 3];
+    server.read_exact(&mut read)?;
+    assert_eq!(read, buf);
+    let mut rest = [0_u8; 5];
+    server.read_exact(&mut rest)?;
+    assert_eq!(&rest, b"hello");
+    Ok(())
+}
+
+/// Peeks with `peek` until `buf` is full, since a peek at a socket returns
+/// only what has arrived so far.
+fn peek_until_full(
+    mut peek: impl FnMut(&mut [u8]) -> io::Result<usize>,
+    buf: &mut [u8],
+) -> io::Result<()> {
+    loop {
+        match peek(buf)? {
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            n if n == buf.len() => return Ok(()),
+            _ => std::thread::yield_now(),
+        }
+    }
+}
+
+#[cfg(unix)]
+#[test]
+#[cfg_attr(miri, ignore)] // socket I/O calls foreign functions
+fn unix_stream_recv_with_flags() -> io::Result<()> {
+    use io_extras::os::rustix::RecvFlags;
+    use std::os::unix::net::UnixStream;
+
+    let (mut a, b) = UnixStream::pair()?;
+    a.write_all(b"hello")?;
+
+    let readable = BorrowedReadable::borrow(b.as_grip());
+    let mut buf = [0_u8; 5];
+    assert_eq!(
+        readable.recv_with_flags(&mut buf, RecvFlags::PEEK | RecvFlags::WAITALL)?,
+        (5, 5)
+    );
+    assert_eq!(&buf, b"hello");
+    assert_eq!(
+        readable.recv_with_flags(&mut buf, RecvFlags::empty())?,
+        (5, 5)
+    );
+    assert_eq!(
+        readable
+            .recv_with_flags(&mut buf, RecvFlags::DONTWAIT)
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::WouldBlock
+    );
+    Ok(())
+}
+
+#[cfg(any(not(windows), feature = "os_pipe"))]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn pipe_peekable() -> io::Result<()> {
+    let (reader, mut writer) = os_pipe::pipe()?;
+    writer.write_all(b"GET / HTTP/1.1")?;
+    drop(writer);
+
+    let mut peekable = Peekable::new(reader);
+    let mut buf = [0_u8; 3];
+    assert_eq!(peekable.peek(&mut buf)?, 3);
+    assert_eq!(&buf, b"GET");
+    assert_eq!(peekable.peek(&mut buf)?, 3);
+    assert_eq!(&buf, b"GET");
+
+    let mut all = String::new();
+    peekable.read_to_string(&mut all)?;
+    assert_eq!(all, "GET / HTTP/1.1");
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // TCP I/O calls foreign functions
+fn tcp_stream_peekable() -> io::Result<()> {
+    let listener = TcpListener::bind("localhost:0")?;
+    let mut client = TcpStream::connect(listener.local_addr()?)?;
+    let server = listener.accept()?.0;
+    client.write_all(b"hello")?;
+    drop(client);
+
+    let mut peekable = Peekable::new(server);
+    let mut buf = [0_u8; 5];
+    peek_until_full(|buf| peekable.peek(buf), &mut buf)?;
+    assert_eq!(&buf, b"hello");
+
+    let (mut server, buffered) = peekable.into_parts();
+    assert!(buffered.is_empty());
+    let mut all = String::new();
+    server.read_to_string(&mut all)?;
+    assert_eq!(all, "hello");
+    Ok(())
+}
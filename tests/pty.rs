@@ -0,0 +1,35 @@
+//! Tests for pseudoterminals.
+
+#![cfg(unix)]
+
+use io_extras::pty::{self, CommandPtyExt, WindowSize};
+use std::io::{self, Read};
+use std::process::Command;
+
+#[test]
+#[cfg_attr(miri, ignore)] // process spawning calls foreign functions
+fn child_on_pty() -> io::Result<()> {
+    let (mut master, slave) = pty::open()?;
+    let size = WindowSize {
+        rows: 24,
+        cols: 100,
+    };
+    master.set_window_size(size)?;
+    assert_eq!(master.window_size()?, size);
+
+    // `/dev/tty` can only be opened by a process with a controlling terminal.
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg("test -t 0 && test -t 1 && echo tty; stty size; : </dev/tty && echo ctty")
+        .pty(&slave)?;
+    let mut child = command.spawn()?;
+    drop(command);
+    drop(slave);
+
+    let mut output = String::new();
+    master.read_to_string(&mut output)?;
+    assert!(child.wait()?.success());
+    assert_eq!(output, "tty\r\n24 100\r\nctty\r\n");
+    Ok(())
+}
@@ -0,0 +1,45 @@
+//! Tests for reading into uninitialized buffers.
+
+#![cfg_attr(target_os = "wasi", feature(wasi_ext))]
+#![cfg(not(target_os = "wasi"))]
+#![cfg(any(not(windows), feature = "os_pipe"))]
+
+use io_extras::borrowed::BorrowedReadable;
+use io_extras::grip::AsGrip;
+use std::io::{self, Write};
+use std::mem::MaybeUninit;
+
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn pipe_read_uninit() -> io::Result<()> {
+    let (reader, mut writer) = os_pipe::pipe()?;
+    writer.write_all(b"hello, world")?;
+    drop(writer);
+
+    let mut buf = [MaybeUninit::<u8>::uninit(); 64];
+    let mut readable = BorrowedReadable::borrow(reader.as_grip());
+    let n = readable.read_uninit(&mut buf)?;
+    assert_eq!(n, 12);
+    let init = unsafe { &*(&buf[..n] as *const [MaybeUninit<u8>] as *const [u8]) };
+    assert_eq!(init, b"hello, world");
+    assert_eq!(readable.read_uninit(&mut buf)?, 0);
+    Ok(())
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn pipe_read_bytes_mut() -> io::Result<()> {
+    use io_extras::grip::{FromGrip, IntoGrip};
+    use io_extras::owned::OwnedReadable;
+
+    let (reader, mut writer) = os_pipe::pipe()?;
+    writer.write_all(b"hello, world")?;
+    drop(writer);
+
+    let mut readable = OwnedReadable::from_grip(reader.into_grip());
+    let mut buf = bytes::BytesMut::new();
+    while readable.read_buf(&mut buf)? != 0 {}
+    assert_eq!(&buf[..], b"hello, world");
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! Tests for fd renumbering and stdio redirection.
+
+#![cfg(unix)]
+
+use io_extras::grip::{dup_to, move_above, FromGrip, IntoGrip};
+use io_extras::redirect::{redirect_stdin, redirect_stdout};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, IntoRawFd};
+
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn renumber() -> io::Result<()> {
+    let (reader, writer) = os_pipe::pipe()?;
+
+    let high = move_above(File::open("Cargo.toml")?.into_grip(), 100)?;
+    assert!(high.as_raw_fd() >= 100);
+    let flags = unsafe { libc::fcntl(high.as_raw_fd(), libc::F_GETFD) };
+    assert_ne!(flags & libc::FD_CLOEXEC, 0);
+
+    // Replace the file at `high` with the pipe, without close-on-exec.
+    let target = high.into_raw_fd();
+    let moved = unsafe { dup_to(&writer, target, false)? };
+    assert_eq!(moved.as_raw_fd(), target);
+    let flags = unsafe { libc::fcntl(target, libc::F_GETFD) };
+    assert_eq!(flags & libc::FD_CLOEXEC, 0);
+    drop(writer);
+
+    File::from_grip(moved).write_all(b"hello")?;
+    let mut buf = String::new();
+    File::from_grip(reader.into_grip()).read_to_string(&mut buf)?;
+    assert_eq!(buf, "hello");
+    Ok(())
+}
+
+// Redirecting stdio affects the whole process, so do it all in one test.
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn redirect() -> io::Result<()> {
+    // Output written straight to fd 1, as a C library would, and output
+    // buffered in `std::io::stdout`, both go to the pipe.
+    let (mut reader, writer) = os_pipe::pipe()?;
+    let guard = redirect_stdout(&writer)?;
+    drop(writer);
+    assert_eq!(unsafe { libc::write(1, b"raw ".as_ptr().cast(), 4) }, 4);
+    io::stdout().write_all(b"buffered")?;
+    guard.restore()?;
+
+    let mut buf = String::new();
+    reader.read_to_string(&mut buf)?;
+    assert_eq!(buf, "raw buffered");
+
+    // Reading straight from fd 0 reads from the pipe.
+    let (reader, mut writer) = os_pipe::pipe()?;
+    writer.write_all(b"input")?;
+    drop(writer);
+    {
+        let _guard = redirect_stdin(&reader)?;
+        let mut buf = [0_u8; 8];
+        let n = unsafe { libc::read(0, buf.as_mut_ptr().cast(), buf.len()) };
+        assert_eq!(&buf[..n as usize], b"input");
+    }
+
+    // After the guard is dropped, fd 0 no longer refers to the pipe.
+    let mut stat = std::mem::MaybeUninit::<libc::stat>::uninit();
+    let mut pipe_stat = std::mem::MaybeUninit::<libc::stat>::uninit();
+    unsafe {
+        libc::fstat(0, stat.as_mut_ptr());
+        libc::fstat(reader.as_raw_fd(), pipe_stat.as_mut_ptr());
+        assert_ne!(
+            (stat.assume_init().st_dev, stat.assume_init().st_ino),
+            (
+                pipe_stat.assume_init().st_dev,
+                pipe_stat.assume_init().st_ino
+            )
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! Tests for reopening grips via procfs.
+
+#![cfg(any(target_os = "android", target_os = "linux"))]
+
+use io_extras::grip::{reopen, FromGrip, IntoGrip};
+use io_extras::os::rustix::OFlags;
+use io_lifetimes::OwnedFd;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::net::UdpSocket;
+use std::os::unix::io::FromRawFd;
+
+#[test]
+#[cfg_attr(miri, ignore)] // file I/O calls foreign functions
+fn reopen_has_own_offset() -> io::Result<()> {
+    let mut file = File::open("Cargo.toml")?;
+    let mut buf = [0_u8; 9];
+    file.read_exact(&mut buf)?;
+
+    let mut reopened = File::from_grip(reopen(&file, OFlags::RDONLY)?);
+    assert_eq!(reopened.stream_position()?, 0);
+    let mut contents = String::new();
+    reopened.read_to_string(&mut contents)?;
+    assert!(contents.starts_with("[package]"));
+    assert_eq!(file.stream_position()?, 9);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // file I/O calls foreign functions
+fn reopen_access_mode() -> io::Result<()> {
+    let path = std::env::temp_dir().join("io-extras-test-reopen-access-mode");
+    let mut file = File::create(&path)?;
+    file.write_all(b"hello")?;
+
+    let mut reopened = File::from_grip(reopen(&file, OFlags::RDONLY)?);
+    assert!(reopened.write_all(b"nope").is_err());
+    reopened.seek(SeekFrom::Start(1))?;
+    let mut buf = String::new();
+    reopened.read_to_string(&mut buf)?;
+    assert_eq!(buf, "ello");
+
+    std::fs::remove_file(path)
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn reopen_pipe() -> io::Result<()> {
+    let (reader, writer) = os_pipe::pipe()?;
+    let mut reopened = File::from_grip(reopen(&writer, OFlags::WRONLY)?);
+    drop(writer);
+    reopened.write_all(b"hello")?;
+    drop(reopened);
+
+    let mut buf = String::new();
+    File::from_grip(reader.into_grip()).read_to_string(&mut buf)?;
+    assert_eq!(buf, "hello");
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // socket I/O calls foreign functions
+fn reopen_socket_fails() -> io::Result<()> {
+    let socket = UdpSocket::bind("127.0.0.1:0")?;
+    let err = reopen(&socket, OFlags::RDWR).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // eventfd calls foreign functions
+fn reopen_anonymous_fails() -> io::Result<()> {
+    let raw = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC) };
+    if raw == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let eventfd = unsafe { OwnedFd::from_raw_fd(raw) };
+    let err = reopen(&eventfd, OFlags::RDWR).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Tests for half-closing sockets and duplex handles.
+
+#![cfg(any(unix, windows))]
+
+use io_extras::borrowed::{BorrowedReadable, BorrowedWriteable};
+use io_extras::grip::{shutdown_write, AsGrip, IntoGrip};
+use io_extras::owned::OwnedReadWrite;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+fn tcp_pair() -> io::Result<(TcpStream, TcpStream)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let client = TcpStream::connect(listener.local_addr()?)?;
+    let (server, _) = listener.accept()?;
+    Ok((client, server))
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // socket I/O calls foreign functions
+fn socket_shutdown_write() -> io::Result<()> {
+    let (mut client, mut server) = tcp_pair()?;
+    client.write_all(b"request")?;
+    BorrowedWriteable::borrow(client.as_grip()).shutdown_write()?;
+
+    // The server sees end-of-stream, and can still reply.
+    let mut buf = String::new();
+    server.read_to_string(&mut buf)?;
+    assert_eq!(buf, "request");
+    server.write_all(b"response")?;
+    drop(server);
+
+    buf.clear();
+    client.read_to_string(&mut buf)?;
+    assert_eq!(buf, "response");
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // socket I/O calls foreign functions
+fn socket_shutdown_read() -> io::Result<()> {
+    let (client, _server) = tcp_pair()?;
+    let mut readable = BorrowedReadable::borrow(client.as_grip());
+    readable.shutdown_read()?;
+    let mut buf = [0_u8; 8];
+    assert_eq!(readable.read(&mut buf)?, 0);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // socket I/O calls foreign functions
+fn read_write_shutdown_write() -> io::Result<()> {
+    let (mut client, mut server) = tcp_pair()?;
+    client.write_all(b"request")?;
+    shutdown_write(&client)?;
+
+    let mut buf = String::new();
+    server.read_to_string(&mut buf)?;
+    assert_eq!(buf, "request");
+    Ok(())
+}
+
+#[cfg(any(not(windows), feature = "os_pipe"))]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn pipe_shutdown_fails() -> io::Result<()> {
+    let (_reader, writer) = os_pipe::pipe()?;
+    assert!(BorrowedWriteable::borrow(writer.as_grip())
+        .shutdown_write()
+        .is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // socket I/O calls foreign functions
+fn duplex_socket() -> io::Result<()> {
+    let (client, mut server) = tcp_pair()?;
+    let mut duplex = OwnedReadWrite::from_grip(client.into_grip());
+    duplex.write_all(b"request")?;
+    duplex.shutdown_write()?;
+    assert!(duplex.write_grip().is_some());
+
+    let mut buf = String::new();
+    server.read_to_string(&mut buf)?;
+    assert_eq!(buf, "request");
+    server.write_all(b"response")?;
+    drop(server);
+
+    buf.clear();
+    duplex.read_to_string(&mut buf)?;
+    assert_eq!(buf, "response");
+    Ok(())
+}
+
+#[cfg(any(not(windows), feature = "os_pipe"))]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn duplex_pipes() -> io::Result<()> {
+    // Connect a duplex to a loopback of two pipes, so that what it writes is
+    // what it reads.
+    let (reader, writer) = os_pipe::pipe()?;
+    let mut duplex = OwnedReadWrite::from_pair(reader.into_grip(), writer.into_grip());
+    duplex.write_all(b"hello")?;
+
+    // Closing the writing end lets the reader see end-of-stream.
+    duplex.shutdown_write()?;
+    assert!(duplex.write_grip().is_none());
+    assert_eq!(
+        duplex.write(b"more").unwrap_err().kind(),
+        io::ErrorKind::BrokenPipe
+    );
+
+    let mut buf = String::new();
+    duplex.read_to_string(&mut buf)?;
+    assert_eq!(buf, "hello");
+
+    duplex.shutdown_read()?;
+    assert!(duplex.read_grip().is_none());
+    assert_eq!(duplex.read(&mut [0_u8; 4])?, 0);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // socket I/O calls foreign functions
+fn pair_of_dup_sockets_shutdown_write() -> io::Result<()> {
+    let (mut client, server) = tcp_pair()?;
+    let read = server.try_clone()?;
+    let mut rw = OwnedReadWrite::from_pair(read.into_grip(), server.into_grip());
+    rw.write_all(b"response")?;
+    rw.shutdown_write()?;
+
+    // The reading handle keeps the socket open, but the peer still sees
+    // end-of-stream, and can still send.
+    client.set_read_timeout(Some(std::time::Duration::from_secs(10)))?;
+    let mut buf = String::new();
+    client.read_to_string(&mut buf)?;
+    assert_eq!(buf, "response");
+    client.write_all(b"more")?;
+    drop(client);
+
+    buf.clear();
+    rw.read_to_string(&mut buf)?;
+    assert_eq!(buf, "more");
+    Ok(())
+}
@@ -0,0 +1,58 @@
+//! Tests for `NoSigpipe` and `is_peer_gone`.
+
+#![cfg(unix)]
+
+use io_extras::borrowed::BorrowedWriteable;
+use io_extras::grip::AsGrip;
+use io_extras::sigpipe::{is_peer_gone, NoSigpipe};
+use std::io::{self, Write};
+use std::os::unix::net::UnixStream;
+
+/// Rust programs ignore `SIGPIPE` by default; restore the default action so
+/// that these tests fail loudly if a `SIGPIPE` escapes.
+fn default_sigpipe() {
+    unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn pipe_no_sigpipe() -> io::Result<()> {
+    default_sigpipe();
+    let (reader, writer) = os_pipe::pipe()?;
+    drop(reader);
+
+    let err = NoSigpipe::new(&writer).write(b"hello").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    assert!(is_peer_gone(&err));
+
+    let writeable = BorrowedWriteable::borrow(writer.as_grip());
+    let err = writeable.no_sigpipe().write_all(b"hello").unwrap_err();
+    assert!(is_peer_gone(&err));
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // socket I/O calls foreign functions
+fn unix_stream_no_sigpipe() -> io::Result<()> {
+    default_sigpipe();
+    let (a, b) = UnixStream::pair()?;
+    drop(b);
+
+    let err = NoSigpipe::new(&a)
+        .write_vectored(&[io::IoSlice::new(b"hello")])
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    assert!(is_peer_gone(&err));
+    Ok(())
+}
+
+#[test]
+fn classify() {
+    assert!(is_peer_gone(&io::Error::from(
+        io::ErrorKind::ConnectionReset
+    )));
+    assert!(is_peer_gone(&io::Error::from_raw_os_error(libc::EIO)));
+    assert!(!is_peer_gone(&io::Error::from(io::ErrorKind::NotFound)));
+}
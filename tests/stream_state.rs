@@ -0,0 +1,74 @@
+//! Tests for `bytes_available` and `is_peer_closed`.
+
+#![cfg(any(unix, windows))]
+
+use io_extras::borrowed::BorrowedReadable;
+use io_extras::grip::{bytes_available, is_peer_closed, AsGrip};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+#[test]
+#[cfg_attr(miri, ignore)] // file I/O calls foreign functions
+fn file_bytes_available() -> io::Result<()> {
+    let mut file = File::open("Cargo.toml")?;
+    let len = file.metadata()?.len();
+    assert_eq!(bytes_available(&file)?, len);
+
+    let mut buf = [0_u8; 10];
+    file.read_exact(&mut buf)?;
+    assert_eq!(bytes_available(&file)?, len - 10);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn pipe_bytes_available_and_hangup() -> io::Result<()> {
+    let (reader, mut writer) = os_pipe::pipe()?;
+    assert_eq!(bytes_available(&reader)?, 0);
+    assert!(!is_peer_closed(&reader)?);
+
+    writer.write_all(b"hello")?;
+    let readable = BorrowedReadable::borrow(reader.as_grip());
+    assert_eq!(readable.bytes_available()?, 5);
+
+    drop(writer);
+    assert!(readable.is_peer_closed()?);
+    assert_eq!(readable.bytes_available()?, 5);
+    Ok(())
+}
+
+#[cfg(all(windows, feature = "os_pipe"))]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn pipe_bytes_available() -> io::Result<()> {
+    let (reader, mut writer) = os_pipe::pipe()?;
+    assert_eq!(bytes_available(&reader)?, 0);
+
+    writer.write_all(b"hello")?;
+    let readable = BorrowedReadable::borrow(reader.as_grip());
+    assert_eq!(readable.bytes_available()?, 5);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // TCP I/O calls foreign functions
+fn tcp_stream_hangup() -> io::Result<()> {
+    let listener = TcpListener::bind("localhost:0")?;
+    let mut client = TcpStream::connect(listener.local_addr()?)?;
+    let server = listener.accept()?.0;
+    assert!(!is_peer_closed(&server)?);
+
+    client.write_all(b"bye")?;
+    drop(client);
+
+    // Wait for the data and the hangup to arrive.
+    let mut buf = [0_u8; 3];
+    BorrowedReadable::borrow(server.as_grip()).peek(&mut buf)?;
+    while !is_peer_closed(&server)? {
+        std::thread::yield_now();
+    }
+    assert_eq!(bytes_available(&server)?, 3);
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! Tests for terminal detection, size, and mode control.
+
+#![cfg(unix)]
+
+use io_extras::borrowed::BorrowedReadable;
+use io_extras::grip::AsGrip;
+use io_extras::pty::{self, WindowSize};
+use io_extras::terminal::{is_terminal, terminal_size, RawModeGuard};
+use std::io::{self, Read, Write};
+
+#[test]
+#[cfg_attr(miri, ignore)] // pseudoterminals call foreign functions
+fn detection_and_size() -> io::Result<()> {
+    let (master, slave) = pty::open()?;
+    assert!(is_terminal(&slave));
+    let (reader, _writer) = os_pipe::pipe()?;
+    assert!(!is_terminal(&reader));
+
+    let size = WindowSize {
+        rows: 40,
+        cols: 132,
+    };
+    master.set_window_size(size)?;
+    assert_eq!(terminal_size(&slave)?, size);
+    assert!(terminal_size(&reader).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // pseudoterminals call foreign functions
+fn no_echo_restores() -> io::Result<()> {
+    let (mut master, slave) = pty::open()?;
+    let mut reader = BorrowedReadable::borrow(slave.as_grip());
+    let mut buf = [0; 64];
+
+    {
+        let _guard = RawModeGuard::no_echo(&slave)?;
+        master.write_all(b"secret\n")?;
+        let n = reader.read(&mut buf)?;
+        assert_eq!(&buf[..n], b"secret\n");
+
+        // Only the newline is echoed.
+        let n = master.read(&mut buf)?;
+        assert_eq!(&buf[..n], b"\r\n");
+    }
+
+    // With the mode restored, input is echoed.
+    master.write_all(b"shown\n")?;
+    let n = master.read(&mut buf)?;
+    assert!(buf[..n].starts_with(b"shown"));
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // pseudoterminals call foreign functions
+fn raw_is_byte_by_byte() -> io::Result<()> {
+    let (mut master, slave) = pty::open()?;
+    let mut reader = BorrowedReadable::borrow(slave.as_grip());
+    let mut buf = [0; 64];
+
+    let guard = RawModeGuard::raw(&slave)?;
+    // Without line editing, input is available before a newline.
+    master.write_all(b"x")?;
+    let n = reader.read(&mut buf)?;
+    assert_eq!(&buf[..n], b"x");
+    drop(guard);
+    Ok(())
+}
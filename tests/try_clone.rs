@@ -0,0 +1,126 @@
+//! Tests for cloning adapters and shared-reference I/O.
+
+#![cfg(any(unix, windows))]
+
+#[cfg(any(not(windows), feature = "os_pipe"))]
+use io_extras::borrowed::{BorrowedReadable, BorrowedWriteable};
+#[cfg(any(not(windows), feature = "os_pipe"))]
+use io_extras::grip::{AsGrip, FromGrip, IntoGrip};
+#[cfg(any(not(windows), feature = "os_pipe"))]
+use io_extras::owned::{OwnedReadWrite, OwnedReadable, OwnedWriteable};
+use io_extras::read_write::{ReadHalf, WriteHalf};
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+#[cfg(any(not(windows), feature = "os_pipe"))]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn owned_try_clone() -> io::Result<()> {
+    let (reader, writer) = os_pipe::pipe()?;
+    let readable = OwnedReadable::from_grip(reader.into_grip());
+    let writeable = OwnedWriteable::from_grip(writer.into_grip());
+
+    let mut writer_clone = writeable.try_clone()?;
+    writer_clone.write_all(b"hello")?;
+    drop(writeable);
+    drop(writer_clone);
+
+    // Reading through the clone continues where the original left off.
+    let mut reader_clone = readable.try_clone()?;
+    let mut buf = [0_u8; 2];
+    (&readable).read_exact(&mut buf)?;
+    assert_eq!(&buf, b"he");
+    let mut rest = String::new();
+    reader_clone.read_to_string(&mut rest)?;
+    assert_eq!(rest, "llo");
+    Ok(())
+}
+
+#[cfg(any(not(windows), feature = "os_pipe"))]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn shared_reference_io() -> io::Result<()> {
+    let (reader, writer) = os_pipe::pipe()?;
+    let readable = OwnedReadable::from_grip(reader.into_grip());
+    let writeable = OwnedWriteable::from_grip(writer.into_grip());
+
+    let shared = &writeable;
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            let mut shared = shared;
+            shared.write_all(b"from a thread").unwrap();
+        });
+    });
+    drop(writeable);
+
+    let mut buf = String::new();
+    (&readable).read_to_string(&mut buf)?;
+    assert_eq!(buf, "from a thread");
+    Ok(())
+}
+
+#[cfg(any(not(windows), feature = "os_pipe"))]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn borrowed_try_clone_to_owned() -> io::Result<()> {
+    let (reader, writer) = os_pipe::pipe()?;
+    let mut writeable = BorrowedWriteable::borrow(writer.as_grip()).try_clone_to_owned()?;
+    drop(writer);
+    writeable.write_all(b"hello")?;
+    drop(writeable);
+
+    let mut readable = BorrowedReadable::borrow(reader.as_grip()).try_clone_to_owned()?;
+    drop(reader);
+    let mut buf = String::new();
+    readable.read_to_string(&mut buf)?;
+    assert_eq!(buf, "hello");
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)] // socket I/O calls foreign functions
+fn halves_try_clone_to_owned() -> io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let client = TcpStream::connect(listener.local_addr()?)?;
+    let (server, _) = listener.accept()?;
+
+    let mut writeable = WriteHalf::new(&client).try_clone_to_owned()?;
+    let mut readable = ReadHalf::new(&server).try_clone_to_owned()?;
+    writeable.write_all(b"hello")?;
+    let mut buf = [0_u8; 5];
+    readable.read_exact(&mut buf)?;
+    assert_eq!(&buf, b"hello");
+    Ok(())
+}
+
+#[cfg(any(not(windows), feature = "os_pipe"))]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn read_write_try_clone() -> io::Result<()> {
+    let (reader, writer) = os_pipe::pipe()?;
+    let mut duplex = OwnedReadWrite::from_pair(reader.into_grip(), writer.into_grip());
+    let mut clone = duplex.try_clone()?;
+
+    clone.write_all(b"hello")?;
+    let mut buf = [0_u8; 5];
+    duplex.read_exact(&mut buf)?;
+    assert_eq!(&buf, b"hello");
+
+    duplex.shutdown_write()?;
+    assert!(duplex.try_clone()?.write_grip().is_none());
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn clone_is_cloexec() -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let (reader, _writer) = os_pipe::pipe()?;
+    let readable = OwnedReadable::from_grip(reader.into_grip());
+    let clone = readable.try_clone()?;
+    let flags = unsafe { libc::fcntl(clone.as_grip().as_raw_fd(), libc::F_GETFD) };
+    assert_ne!(flags & libc::FD_CLOEXEC, 0);
+    Ok(())
+}
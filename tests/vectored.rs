@@ -0,0 +1,98 @@
+//! Tests for the stable vectored I/O helpers.
+
+#[cfg(any(not(windows), feature = "os_pipe"))]
+use io_extras::borrowed::{BorrowedReadable, BorrowedWriteable};
+#[cfg(any(not(windows), feature = "os_pipe"))]
+use io_extras::grip::AsGrip;
+use io_extras::vectored::{read_exact_vectored, write_all_vectored};
+use std::io::{self, IoSlice, IoSliceMut, Write};
+
+/// A writer which writes at most three bytes at a time.
+struct Short(Vec<u8>);
+
+impl Write for Short {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = buf.len().min(3);
+        self.0.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn short_writes() -> io::Result<()> {
+    let mut short = Short(Vec::new());
+    let mut bufs = [
+        IoSlice::new(b""),
+        IoSlice::new(b"hello"),
+        IoSlice::new(b""),
+        IoSlice::new(b", "),
+        IoSlice::new(b"world"),
+    ];
+    write_all_vectored(&mut short, &mut bufs)?;
+    assert_eq!(short.0, b"hello, world");
+    Ok(())
+}
+
+#[test]
+fn short_reads() -> io::Result<()> {
+    let mut data: &[u8] = b"hello, world";
+    let (mut a, mut b, mut c) = ([0_u8; 5], [0_u8; 0], [0_u8; 7]);
+    let mut bufs = [
+        IoSliceMut::new(&mut a),
+        IoSliceMut::new(&mut b),
+        IoSliceMut::new(&mut c),
+    ];
+    read_exact_vectored(&mut data, &mut bufs)?;
+    assert_eq!(&a, b"hello");
+    assert_eq!(&c, b", world");
+
+    let mut data: &[u8] = b"hi";
+    let mut d = [0_u8; 3];
+    assert_eq!(
+        read_exact_vectored(&mut data, &mut [IoSliceMut::new(&mut d)])
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::UnexpectedEof
+    );
+    Ok(())
+}
+
+#[cfg(any(not(windows), feature = "os_pipe"))]
+#[test]
+#[cfg_attr(miri, ignore)] // pipe I/O calls foreign functions
+fn pipe_vectored() -> io::Result<()> {
+    let (reader, writer) = os_pipe::pipe()?;
+    let mut writeable = BorrowedWriteable::borrow(writer.as_grip());
+    #[cfg(not(windows))]
+    assert!(writeable.is_write_vectored());
+    #[cfg(windows)]
+    assert_eq!(
+        writeable.is_write_vectored(),
+        io_extras::vectored::is_write_vectored(&writer)
+    );
+    write_all_vectored(
+        &mut writeable,
+        &mut [IoSlice::new(b"hello"), IoSlice::new(b", world")],
+    )?;
+
+    let mut readable = BorrowedReadable::borrow(reader.as_grip());
+    #[cfg(not(windows))]
+    assert!(readable.is_read_vectored());
+    #[cfg(windows)]
+    assert_eq!(
+        readable.is_read_vectored(),
+        io_extras::vectored::is_read_vectored(&reader)
+    );
+    let (mut a, mut b) = ([0_u8; 7], [0_u8; 5]);
+    read_exact_vectored(
+        &mut readable,
+        &mut [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)],
+    )?;
+    assert_eq!(&a, b"hello, ");
+    assert_eq!(&b, b"world");
+    Ok(())
+}